@@ -36,4 +36,14 @@ echo "📄 WASM file: target/wasm32-unknown-unknown/release/sep41_token.wasm"
 
 # Copy WASM to parent directory for easy access
 cp target/wasm32-unknown-unknown/release/sep41_token.wasm ../sep41_token.wasm
-echo "📄 Contract copied to: contracts/sep41_token.wasm"
\ No newline at end of file
+echo "📄 Contract copied to: contracts/sep41_token.wasm"
+
+# Copy WASM to the frontend as well, which serves it for in-browser deploys
+cp target/wasm32-unknown-unknown/release/sep41_token.wasm ../../public/contracts/sep41_token.wasm
+echo "📄 Contract copied to: public/contracts/sep41_token.wasm"
+
+# Build the factory, which embeds the token interface from contracts/sep41_token.wasm
+echo "📦 Compiling token factory to WASM..."
+cd ../token_factory
+cargo build --target wasm32-unknown-unknown --release
+echo "📄 Factory WASM file: contracts/token_factory/target/wasm32-unknown-unknown/release/token_factory.wasm"
\ No newline at end of file
@@ -1,5 +1,11 @@
-use crate::storage_types::{DataKey, TokenState, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
-use soroban_sdk::{Address, Env};
+use crate::error::TokenError;
+use crate::governance::{read_admin_set, read_executing_admin};
+use crate::stats::assert_supply_invariant;
+use crate::storage_types::{
+    DataKey, PendingAdmin, PendingTimelock, TokenState, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Address, Env};
 
 pub fn has_administrator(e: &Env) -> bool {
     let key = DataKey::Admin;
@@ -17,6 +23,79 @@ pub fn write_administrator(e: &Env, id: Address) {
     e.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    append_admin_history(e, id);
+}
+
+// Each admin change is its own persistent entry, so the history can grow without
+// any single entry growing with it
+fn append_admin_history(e: &Env, id: Address) {
+    let len = read_admin_history_len(e);
+    let key = DataKey::AdminHistory(len);
+    e.storage().persistent().set(&key, &(e.ledger().sequence(), id));
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    e.storage().instance().set(&DataKey::AdminHistoryLen, &(len + 1));
+}
+
+fn read_admin_history_len(e: &Env) -> u32 {
+    e.storage().instance().get(&DataKey::AdminHistoryLen).unwrap_or(0)
+}
+
+fn read_admin_history(e: &Env, index: u32) -> (u32, Address) {
+    e.storage().persistent().get(&DataKey::AdminHistory(index)).unwrap()
+}
+
+pub fn read_administrator_at(e: &Env, ledger: u32) -> Option<Address> {
+    // Binary search for the last change at or before `ledger`; changes are appended
+    // in ledger order
+    let (mut low, mut high) = (0, read_admin_history_len(e));
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_admin_history(e, mid).0 <= ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        return None;
+    }
+    Some(read_admin_history(e, low - 1).1)
+}
+
+pub fn read_pending_admin(e: &Env) -> Option<PendingAdmin> {
+    let key = DataKey::PendingAdmin;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_pending_admin(e: &Env, pending: Option<PendingAdmin>) {
+    let key = DataKey::PendingAdmin;
+    match pending {
+        Some(pending) => e.storage().instance().set(&key, &pending),
+        None => e.storage().instance().remove(&key),
+    }
+}
+
+pub fn read_pending_admin_timelock(e: &Env) -> Option<PendingTimelock> {
+    let key = DataKey::PendingAdminTimelock;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_pending_admin_timelock(e: &Env, pending: Option<PendingTimelock>) {
+    let key = DataKey::PendingAdminTimelock;
+    match pending {
+        Some(pending) => e.storage().instance().set(&key, &pending),
+        None => e.storage().instance().remove(&key),
+    }
+}
+
+/// The admin timelock in force, counting a lowered one once its wait has passed
+pub fn read_admin_timelock(e: &Env) -> u32 {
+    match read_pending_admin_timelock(e) {
+        Some(pending) if e.ledger().sequence() >= pending.ready_at => pending.ledgers,
+        _ => read_state(e).admin_timelock_ledgers,
+    }
 }
 
 pub fn read_state(e: &Env) -> TokenState {
@@ -25,6 +104,7 @@ pub fn read_state(e: &Env) -> TokenState {
 }
 
 pub fn write_state(e: &Env, state: TokenState) {
+    assert_supply_invariant(e, &state);
     let key = DataKey::State;
     e.storage().instance().set(&key, &state);
     e.storage()
@@ -32,7 +112,17 @@ pub fn write_state(e: &Env, state: TokenState) {
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 }
 
-pub fn check_admin(e: &Env) {
+/// Require admin authorization, returning the admin acting: the stored admin, or the
+/// approver whose approval is executing the action while an admin set is configured.
+pub fn check_admin(e: &Env) -> Address {
+    if !has_administrator(e) {
+        panic_with_error!(e, TokenError::NotInitialized);
+    }
+    // With an admin set configured, only actions approved by its threshold may run
+    if read_admin_set(e).is_some() {
+        return read_executing_admin(e).unwrap_or_else(|| panic_with_error!(e, TokenError::ThresholdRequired));
+    }
     let admin = read_administrator(e);
     admin.require_auth();
+    admin
 }
\ No newline at end of file
@@ -1,7 +1,17 @@
+use crate::error::{GuardError, TokenError};
 use crate::storage_types::{
-    AllowanceDataKey, AllowanceValue, DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+    AllowanceDataKey, AllowanceValue, DataKey, OperatorDataKey, BALANCE_BUMP_AMOUNT,
+    BALANCE_LIFETIME_THRESHOLD,
 };
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{panic_with_error, vec, Address, Env, Vec};
+
+/// An allowance of `i128::MAX` is infinite: `spend_allowance` never decrements it,
+/// so it lasts until revoked or expired.
+pub const INFINITE_ALLOWANCE: i128 = i128::MAX;
+
+/// Number of spenders an owner may hold live allowances for at once, which bounds
+/// the `AllowanceList` entry rewritten whenever a spender is added or removed.
+pub const MAX_TRACKED_SPENDERS: u32 = 50;
 
 pub fn read_allowance(e: &Env, from: Address, spender: Address) -> AllowanceValue {
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
@@ -34,22 +44,88 @@ pub fn write_allowance(
         expiration_ledger,
     };
 
+    track_spender(e, &from, &spender, amount > 0);
+
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
     e.storage().temporary().set(&key, &allowance);
 
-    if expiration_ledger < e.ledger().sequence() {
-        e.storage().temporary().extend_ttl(
-            &key,
-            BALANCE_LIFETIME_THRESHOLD,
-            BALANCE_BUMP_AMOUNT,
-        );
+    // Keep the entry live until it expires
+    if amount > 0 {
+        let live_for = expiration_ledger.saturating_sub(e.ledger().sequence());
+        e.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+}
+
+/// Spenders `from` has granted a nonzero allowance to, in the order first approved.
+pub fn read_allowance_spenders(e: &Env, from: &Address) -> Vec<Address> {
+    let key = DataKey::AllowanceList(from.clone());
+    e.storage().persistent().get(&key).unwrap_or(vec![e])
+}
+
+fn track_spender(e: &Env, from: &Address, spender: &Address, active: bool) {
+    let key = DataKey::AllowanceList(from.clone());
+    let mut spenders = read_allowance_spenders(e, from);
+    match (spenders.first_index_of(spender), active) {
+        (None, true) => {
+            if spenders.len() >= MAX_TRACKED_SPENDERS {
+                panic_with_error!(e, GuardError::TooManySpenders);
+            }
+            spenders.push_back(spender.clone());
+        }
+        (Some(index), false) => {
+            spenders.remove(index);
+        }
+        _ => return,
+    }
+
+    if spenders.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &spenders);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+}
+
+/// Remove an allowance whose expiration ledger has passed, and stop tracking its
+/// spender, returning whether an entry was removed.
+pub fn prune_expired_allowance(e: &Env, from: Address, spender: Address) -> bool {
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    match e.storage().temporary().get::<DataKey, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger < e.ledger().sequence() => {
+            e.storage().temporary().remove(&key);
+            track_spender(e, &from, &spender, false);
+            true
+        }
+        // An entry whose TTL ran out is already gone, but may still be tracked
+        None => {
+            track_spender(e, &from, &spender, false);
+            false
+        }
+        _ => false,
     }
 }
 
 pub fn spend_allowance(e: &Env, from: Address, spender: Address, amount: i128) {
     let allowance = read_allowance(e, from.clone(), spender.clone());
     if allowance.amount < amount {
-        panic!("insufficient allowance");
+        panic_with_error!(e, TokenError::InsufficientAllowance);
+    }
+
+    if allowance.amount == INFINITE_ALLOWANCE {
+        return;
+    }
+
+    // A fully spent allowance is removed rather than kept at zero
+    if allowance.amount == amount {
+        track_spender(e, &from, &spender, false);
+        let key = DataKey::Allowance(AllowanceDataKey { from, spender });
+        e.storage().temporary().remove(&key);
+        return;
     }
     write_allowance(
         e,
@@ -58,4 +134,28 @@ pub fn spend_allowance(e: &Env, from: Address, spender: Address, amount: i128) {
         allowance.amount - amount,
         allowance.expiration_ledger,
     );
+}
+
+pub fn is_operator(e: &Env, owner: Address, operator: Address) -> bool {
+    let key = DataKey::Operator(OperatorDataKey { owner, operator });
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        true
+    } else {
+        false
+    }
+}
+
+pub fn write_operator(e: &Env, owner: Address, operator: Address, approved: bool) {
+    let key = DataKey::Operator(OperatorDataKey { owner, operator });
+    if approved {
+        e.storage().persistent().set(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
 }
\ No newline at end of file
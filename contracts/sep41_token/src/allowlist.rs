@@ -0,0 +1,46 @@
+use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{Address, Env};
+
+pub fn is_allowlisted(e: &Env, addr: &Address) -> bool {
+    let key = DataKey::Allowed(addr.clone());
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        true
+    } else {
+        false
+    }
+}
+
+pub fn write_allowlisted(e: &Env, addr: &Address, allowed: bool) {
+    let key = DataKey::Allowed(addr.clone());
+    if allowed {
+        e.storage().persistent().set(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_opted_in(e: &Env, addr: &Address) -> bool {
+    let key = DataKey::OptedIn(addr.clone());
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        true
+    } else {
+        false
+    }
+}
+
+pub fn write_opted_in(e: &Env, addr: &Address) {
+    let key = DataKey::OptedIn(addr.clone());
+    e.storage().persistent().set(&key, &true);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
\ No newline at end of file
@@ -1,5 +1,12 @@
-use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
-use soroban_sdk::{Address, Env};
+use crate::circulation::record_balance_change;
+use crate::error::TokenError;
+use crate::limits::record_receipt;
+use crate::snapshot::record_checkpoint;
+use crate::storage_types::{
+    DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Address, Env};
 
 pub fn read_balance(e: &Env, addr: Address) -> i128 {
     let key = DataKey::Balance(addr);
@@ -13,6 +20,14 @@ pub fn read_balance(e: &Env, addr: Address) -> i128 {
     }
 }
 
+/// Read a balance without extending its TTL
+pub fn peek_balance(e: &Env, addr: Address) -> i128 {
+    e.storage()
+        .persistent()
+        .get::<DataKey, i128>(&DataKey::Balance(addr))
+        .unwrap_or(0)
+}
+
 fn write_balance(e: &Env, addr: Address, amount: i128) {
     let key = DataKey::Balance(addr);
     e.storage().persistent().set(&key, &amount);
@@ -21,20 +36,72 @@ fn write_balance(e: &Env, addr: Address, amount: i128) {
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
 }
 
+pub fn read_holder_count(e: &Env) -> u64 {
+    let key = DataKey::HolderCount;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+fn write_holder_count(e: &Env, count: u64) {
+    let key = DataKey::HolderCount;
+    e.storage().instance().set(&key, &count);
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
 pub fn receive_balance(e: &Env, addr: Address, amount: i128) {
     let balance = read_balance(e, addr.clone());
+    if balance == 0 && amount > 0 {
+        write_holder_count(e, read_holder_count(e) + 1);
+    }
+    record_checkpoint(e, addr.clone(), balance);
+    record_receipt(e, addr.clone());
+    record_balance_change(e, &addr, amount);
     write_balance(e, addr, balance + amount);
 }
 
-pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
+pub fn read_locked(e: &Env, addr: Address) -> i128 {
+    let key = DataKey::Locked(addr);
+    if let Some(locked) = e.storage().persistent().get::<DataKey, i128>(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        locked
+    } else {
+        0
+    }
+}
+
+pub fn write_locked(e: &Env, addr: Address, amount: i128) {
+    let key = DataKey::Locked(addr);
+    if amount == 0 {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage().persistent().set(&key, &amount);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Panic unless `addr` holds `amount` outside of its locked balance.
+pub fn check_spendable(e: &Env, addr: Address, amount: i128) {
     let balance = read_balance(e, addr.clone());
     if balance < amount {
-        panic!("insufficient balance");
+        panic_with_error!(e, TokenError::InsufficientBalance);
+    }
+    if balance - read_locked(e, addr) < amount {
+        panic_with_error!(e, TokenError::BalanceLocked);
     }
-    write_balance(e, addr, balance - amount);
 }
 
-pub fn is_authorized(e: &Env, addr: Address) -> bool {
-    addr.require_auth();
-    true
+pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
+    check_spendable(e, addr.clone(), amount);
+    let balance = read_balance(e, addr.clone());
+    if balance > 0 && balance == amount {
+        write_holder_count(e, read_holder_count(e) - 1);
+    }
+    record_checkpoint(e, addr.clone(), balance);
+    record_balance_change(e, &addr, -amount);
+    write_balance(e, addr, balance - amount);
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use crate::balance::read_balance;
+use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{Address, Env};
+
+pub fn is_excluded(e: &Env, addr: &Address) -> bool {
+    let key = DataKey::Excluded(addr.clone());
+    e.storage().persistent().has(&key)
+}
+
+/// Add or remove `addr` from the excluded set, moving its current balance
+/// into or out of the running excluded total.
+pub fn write_excluded(e: &Env, addr: &Address, excluded: bool) {
+    if excluded == is_excluded(e, addr) {
+        return;
+    }
+    let key = DataKey::Excluded(addr.clone());
+    let balance = read_balance(e, addr.clone());
+    if excluded {
+        e.storage().persistent().set(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        write_excluded_balance(e, read_excluded_balance(e) + balance);
+    } else {
+        e.storage().persistent().remove(&key);
+        write_excluded_balance(e, read_excluded_balance(e) - balance);
+    }
+}
+
+/// Keep the running excluded total in step with a balance change on `addr`.
+pub fn record_balance_change(e: &Env, addr: &Address, delta: i128) {
+    if is_excluded(e, addr) {
+        write_excluded_balance(e, read_excluded_balance(e) + delta);
+    }
+}
+
+pub fn read_excluded_balance(e: &Env) -> i128 {
+    let key = DataKey::ExcludedBalance;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+fn write_excluded_balance(e: &Env, amount: i128) {
+    let key = DataKey::ExcludedBalance;
+    e.storage().instance().set(&key, &amount);
+}
+
+/// Total supply less excluded balances and the contract's own reserve.
+pub fn read_circulating_supply(e: &Env, total_supply: i128) -> i128 {
+    let reserve_addr = e.current_contract_address();
+    let reserve = if is_excluded(e, &reserve_addr) {
+        0
+    } else {
+        read_balance(e, reserve_addr)
+    };
+    total_supply - read_excluded_balance(e) - reserve
+}
\ No newline at end of file
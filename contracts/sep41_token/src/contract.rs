@@ -1,17 +1,59 @@
-use crate::admin::{check_admin, has_administrator, read_administrator, read_state, write_administrator, write_state};
-use crate::allowance::{read_allowance, spend_allowance, write_allowance};
-use crate::balance::{read_balance, receive_balance, spend_balance};
-use crate::metadata::{read_decimal, read_name, read_symbol, write_metadata};
-use crate::storage_types::{TokenMetadata, TokenState, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use crate::admin::{
+    check_admin, has_administrator, read_admin_timelock, read_administrator, read_administrator_at, read_pending_admin,
+    read_state, write_administrator, write_pending_admin, write_pending_admin_timelock, write_state,
+};
+use crate::allowlist::{is_allowlisted, is_opted_in, write_allowlisted, write_opted_in};
+use crate::allowance::{
+    is_operator, prune_expired_allowance, read_allowance, read_allowance_spenders, spend_allowance, write_allowance,
+    write_operator,
+};
+use crate::balance::{
+    check_spendable, peek_balance, read_balance, read_holder_count, read_locked, receive_balance, spend_balance,
+    write_locked,
+};
+use crate::circulation::{read_circulating_supply, write_excluded};
+use crate::error::{GuardError, TokenError};
+use crate::fee::{charge_transfer_fee, validate_fee};
+use crate::freeze::{is_account_frozen, read_freeze_reason, read_frozen_page, write_account_frozen};
+use crate::governance::{
+    action_hash, read_admin_set, read_proposal, remove_proposal, write_admin_set, write_executing_admin, write_proposal,
+};
+use crate::hooks::{notify_guardian, notify_receiver};
+use crate::limits::{
+    check_burn_holding_period, check_transfer_cooldown, check_wallet_limit, read_epoch_minted, record_epoch_mint,
+    record_ledger_transfer, record_rolling_outgoing, record_transfer_ledger, write_cooldown_exempt,
+    write_wallet_limit_exempt,
+};
+use crate::metadata::{read_decimal, read_metadata, read_name, read_symbol, validate_metadata, write_metadata};
+use crate::nonce::{increment_nonce, read_nonce};
+use crate::pagination::page;
+use crate::reversible::{next_reversal_id, read_pending_transfer, remove_pending_transfer, write_pending_transfer};
+use crate::rules::{check_rules, read_rules, record_rules_transfer, write_rules};
+use crate::snapshot::{create_snapshot, read_balance_at, read_snapshot_id, read_snapshot_ledger};
+use crate::stats::{assert_supply_invariant, read_max_transfer, read_supply_history, record_supply_change, record_transfer_amount};
+use crate::storage_types::{AdminAction, AdminSet, Economics, FeeRounding, FrozenStatus, InitialSupply, PendingAdmin, PendingTimelock, PendingTransfer, Proposal, Rule, TokenConfig, TokenMetadata, TokenState, TrancheSchedule, VestingSchedule, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use crate::vesting::{
+    next_tranche_id, read_tranche, read_vesting, remove_tranche, remove_vesting, tranche_unlocked, vested_amount,
+    write_tranche, write_vesting,
+};
 
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, panic_with_error, token, xdr::ToXdr, Address, BytesN, Env, IntoVal, String, Vec};
+
+const MAX_DECIMALS: u32 = 18;
+const MAX_MEMO_LEN: u32 = 64;
+
+/// Bumped whenever an event's topics or data change shape:
+/// 1 - initial events
+/// 2 - `mint`, `freeze` and `set_admin` data lead with the acting admin
+/// 3 - `transfer`, `mint` and `burn` carry their addresses in the topics and only the amount as data
+const EVENT_SCHEMA_VERSION: u32 = 3;
 
 #[contract]
 pub struct TokenContract;
 
 #[contractimpl]
 impl TokenContract {
-    /// Initialize the token contract with metadata and initial parameters
+    /// Initialize the token contract with metadata, parameters and an optional initial supply
     pub fn initialize(
         env: Env,
         admin: Address,
@@ -22,115 +64,342 @@ impl TokenContract {
         is_mintable: bool,
         is_burnable: bool,
         is_freezable: bool,
+        initial_supply: Option<InitialSupply>,
     ) {
         if has_administrator(&env) {
-            panic!("already initialized");
+            panic_with_error!(&env, TokenError::AlreadyInitialized);
+        }
+
+        if decimal > MAX_DECIMALS {
+            panic_with_error!(&env, TokenError::InvalidDecimals);
+        }
+
+        validate_metadata(&env, &name, &symbol);
+
+        // A cap of zero could never be minted against; leave it as `None` for uncapped
+        if matches!(max_supply, Some(max) if max <= 0) {
+            panic_with_error!(&env, TokenError::InvalidConfig);
+        }
+
+        let minted = match &initial_supply {
+            Some(initial) => initial.amount,
+            None => 0,
+        };
+        if minted < 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+        if let Some(max_supply) = max_supply {
+            if minted > max_supply {
+                panic_with_error!(&env, TokenError::ExceedsMaxSupply);
+            }
         }
 
         write_administrator(&env, admin.clone());
         
         let metadata = TokenMetadata {
             decimal,
-            name,
-            symbol,
+            name: name.clone(),
+            symbol: symbol.clone(),
         };
         write_metadata(&env, metadata);
 
         let state = TokenState {
-            admin,
-            total_supply: 0,
+            admin: admin.clone(),
+            total_supply: minted,
+            total_minted: minted,
+            total_burned: 0,
             max_supply,
             is_mintable,
             is_burnable,
             is_freezable,
             is_frozen: false,
+            rolling_cap: None,
+            rolling_window_ledgers: 0,
+            guardian_contract: None,
+            fee_bps: 0,
+            fee_collector: None,
+            fee_rounding: FeeRounding::Down,
+            min_burn_holding_period: 0,
+            reject_contract_recipient: false,
+            reject_mint_to_contract: false,
+            mint_limit_per_epoch: None,
+            epoch_ledgers: 0,
+            max_transfers_per_ledger: None,
+            reversal_window_ledgers: 0,
+            arbiter: None,
+            hooks_enabled: false,
+            allowlist_enabled: false,
+            require_opt_in: false,
+            reject_zero_transfers: false,
+            max_transfer_amount: None,
+            min_transfer_amount: None,
+            max_wallet_balance: None,
+            force_transfer_enabled: false,
+            transfer_cooldown_ledgers: 0,
+            underlying_asset: None,
+            admin_timelock_ledgers: 0,
+            burn_address: None,
+            burned_supply: 0,
         };
         write_state(&env, state);
+
+        env.events().publish(
+            ("initialize",), 
+            (admin.clone(), name, symbol, decimal)
+        );
+
+        if let Some(initial) = initial_supply {
+            record_supply_change(&env, initial.amount, initial.amount);
+            receive_balance(&env, initial.recipient.clone(), initial.amount);
+            env.events().publish(
+                ("mint", admin, initial.recipient), 
+                initial.amount
+            );
+        }
+    }
+
+    /// Initialize a mintable, burnable, non-freezable token with `supply` minted to the
+    /// admin, optionally renouncing further minting in the same call
+    pub fn deploy_basic(
+        env: Env,
+        admin: Address,
+        decimal: u32,
+        name: String,
+        symbol: String,
+        supply: i128,
+        renounce: bool,
+    ) {
+        let initial_supply = InitialSupply {
+            recipient: admin.clone(),
+            amount: supply,
+        };
+        Self::initialize(
+            env.clone(),
+            admin,
+            decimal,
+            name,
+            symbol,
+            None,
+            true,
+            true,
+            false,
+            Some(initial_supply),
+        );
+
+        if renounce {
+            Self::renounce_minting(env);
+        }
+    }
+
+    /// Mint tokens to a specified address, returning the new total supply (admin only)
+    pub fn mint(env: Env, to: Address, amount: i128) -> i128 {
+        let admin = check_admin(&env);
+        Self::check_unbacked_mint(&env);
+        Self::mint_tokens(&env, admin, to, amount)
+    }
+
+    /// Mint as much of `amount` as fits under the max supply, returning the amount
+    /// actually minted, which may be zero; other limits still panic as in `mint`
+    /// (admin only)
+    pub fn mint_up_to(env: Env, to: Address, amount: i128) -> i128 {
+        let admin = check_admin(&env);
+        Self::check_unbacked_mint(&env);
+
+        if amount < 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        let state = read_state(&env);
+        let minted = match state.max_supply {
+            Some(max_supply) => amount.min(max_supply - state.total_supply).max(0),
+            None => amount,
+        };
+        if minted > 0 {
+            Self::mint_tokens(&env, admin, to, minted);
+        }
+        minted
     }
 
-    /// Mint tokens to a specified address (admin only)
-    pub fn mint(env: Env, to: Address, amount: i128) {
+    /// Force-burn tokens from a specified address (admin only)
+    pub fn admin_burn(env: Env, from: Address, amount: i128) {
         check_admin(&env);
-        let mut state = read_state(&env);
+        let state = read_state(&env);
         
-        if !state.is_mintable {
-            panic!("token is not mintable");
+        if !state.is_burnable {
+            panic_with_error!(&env, TokenError::NotBurnable);
         }
 
         if state.is_frozen {
-            panic!("token is globally frozen");
+            panic_with_error!(&env, TokenError::GloballyFrozen);
         }
 
-        // Check max supply constraint
-        if let Some(max_supply) = state.max_supply {
-            if state.total_supply + amount > max_supply {
-                panic!("would exceed max supply");
-            }
+        if state.min_burn_holding_period > 0 {
+            check_burn_holding_period(&env, from.clone(), state.min_burn_holding_period);
+        }
+
+        if read_balance(&env, from.clone()) < amount {
+            panic_with_error!(&env, TokenError::InsufficientBalanceToBurn);
+        }
+
+        Self::burn_balance(&env, state, &from, amount);
+    }
+
+    /// Burn tokens from the caller's own balance
+    pub fn burn_self(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        let state = read_state(&env);
+
+        if !state.is_burnable {
+            panic_with_error!(&env, TokenError::NotBurnable);
+        }
+
+        if state.is_frozen {
+            panic_with_error!(&env, TokenError::GloballyFrozen);
+        }
+
+        if Self::is_frozen(env.clone(), from.clone()) {
+            panic_with_error!(&env, TokenError::Frozen);
+        }
+
+        if state.min_burn_holding_period > 0 {
+            check_burn_holding_period(&env, from.clone(), state.min_burn_holding_period);
+        }
+
+        if read_balance(&env, from.clone()) < amount {
+            panic_with_error!(&env, TokenError::InsufficientBalanceToBurn);
+        }
+
+        Self::burn_balance(&env, state, &from, amount);
+    }
+
+    /// Burn by moving tokens to `burn_address`, keeping total supply constant, or
+    /// reduce supply as usual when `None` (admin only)
+    pub fn set_burn_address(env: Env, burn_address: Option<Address>) {
+        check_admin(&env);
+
+        if burn_address == Some(env.current_contract_address()) {
+            panic_with_error!(&env, TokenError::InvalidConfig);
         }
 
-        state.total_supply += amount;
+        let mut state = read_state(&env);
+        state.burn_address = burn_address.clone();
         write_state(&env, state);
-        
-        receive_balance(&env, to.clone(), amount);
-        
+
         env.events().publish(
-            ("mint",), 
-            (to, amount)
+            ("set_burn_address",), 
+            burn_address
         );
     }
 
-    /// Burn tokens from a specified address (admin only)
-    pub fn burn(env: Env, from: Address, amount: i128) {
+    /// Allow or forbid `force_transfer` (admin only)
+    pub fn set_force_transfer_enabled(env: Env, enabled: bool) {
         check_admin(&env);
         let mut state = read_state(&env);
-        
-        if !state.is_burnable {
-            panic!("token is not burnable");
+        state.force_transfer_enabled = enabled;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_force_transfer_enabled",), 
+            enabled
+        );
+    }
+
+    /// Move tokens out of an account without its auth, e.g. to recover a lost wallet;
+    /// supply is unchanged (admin only, requires force transfers to be enabled)
+    pub fn force_transfer(env: Env, from: Address, to: Address, amount: i128) {
+        check_admin(&env);
+        let state = read_state(&env);
+
+        if !state.force_transfer_enabled {
+            panic_with_error!(&env, TokenError::ForceTransferDisabled);
         }
 
-        if state.is_frozen {
-            panic!("token is globally frozen");
+        // This token's own reserve backs tranches, vesting and pending transfers
+        if from == env.current_contract_address() {
+            panic_with_error!(&env, GuardError::ContractReserve);
+        }
+
+        if amount <= 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
         }
 
         spend_balance(&env, from.clone(), amount);
-        state.total_supply -= amount;
-        write_state(&env, state);
-        
+        receive_balance(&env, to.clone(), amount);
+
         env.events().publish(
-            ("burn",), 
-            (from, amount)
+            ("force_transfer",), 
+            (from, to, amount)
         );
     }
 
-    /// Freeze an account (admin only, requires freezable token)
-    pub fn freeze(env: Env, addr: Address) {
+    /// Distribute tokens from `from` to many recipients in one call, with admin auth
+    /// standing in for `from`'s; the whole batch reverts if any entry fails (admin only,
+    /// requires force transfers to be enabled unless `from` is the admin)
+    pub fn admin_transfer_batch(env: Env, from: Address, recipients: Vec<Address>, amounts: Vec<i128>) {
         check_admin(&env);
+
+        // This token's own reserve backs tranches, vesting and pending transfers
+        if from == env.current_contract_address() {
+            panic_with_error!(&env, GuardError::ContractReserve);
+        }
+
+        if from != read_administrator(&env) && !read_state(&env).force_transfer_enabled {
+            panic_with_error!(&env, TokenError::ForceTransferDisabled);
+        }
+
+        if recipients.len() != amounts.len() {
+            panic_with_error!(&env, GuardError::BatchLengthMismatch);
+        }
+
+        // Validate the whole batch against the balance before moving anything
+        let mut total: i128 = 0;
+        for amount in amounts.iter() {
+            if amount <= 0 {
+                panic_with_error!(&env, TokenError::InvalidAmount);
+            }
+            total = total
+                .checked_add(amount)
+                .unwrap_or_else(|| panic_with_error!(&env, TokenError::Overflow));
+        }
+        check_spendable(&env, from.clone(), total);
+
+        for (to, amount) in recipients.iter().zip(amounts.iter()) {
+            let received = Self::transfer_balance(&env, &from, &to, amount);
+            env.events().publish(
+                ("transfer", from.clone(), to.clone()), 
+                received
+            );
+            Self::notify_receiver(&env, &from, &to, received);
+        }
+    }
+
+    /// Freeze an account (admin only, requires freezable token)
+    pub fn freeze(env: Env, addr: Address, reason: String) {
+        let admin = check_admin(&env);
         let state = read_state(&env);
         
         if !state.is_freezable {
-            panic!("token is not freezable");
+            panic_with_error!(&env, TokenError::NotFreezable);
         }
 
-        // Store frozen account in persistent storage
-        // This is a simplified implementation - in production you'd want a more sophisticated freeze system
-        env.storage().persistent().set(&addr, &true);
+        write_account_frozen(&env, &addr, Some(reason.clone()));
+
+        if let Some(guardian) = &state.guardian_contract {
+            notify_guardian(&env, guardian, &addr);
+        }
         
         env.events().publish(
             ("freeze",), 
-            addr
+            (admin, addr, reason)
         );
     }
 
     /// Unfreeze an account (admin only)
     pub fn unfreeze(env: Env, addr: Address) {
         check_admin(&env);
-        let state = read_state(&env);
-        
-        if !state.is_freezable {
-            panic!("token is not freezable");
-        }
 
-        env.storage().persistent().remove(&addr);
+        // Like set_frozen(false), this stays available after freezing is disabled
+        write_account_frozen(&env, &addr, None);
         
         env.events().publish(
             ("unfreeze",), 
@@ -138,17 +407,65 @@ impl TokenContract {
         );
     }
 
+    /// Lock part of an account's balance so it cannot be moved (admin only)
+    pub fn lock(env: Env, addr: Address, amount: i128) {
+        check_admin(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        let locked = read_locked(&env, addr.clone()) + amount;
+        if locked > read_balance(&env, addr.clone()) {
+            panic_with_error!(&env, TokenError::InsufficientBalance);
+        }
+        write_locked(&env, addr.clone(), locked);
+
+        env.events().publish(
+            ("lock",), 
+            (addr, amount)
+        );
+    }
+
+    /// Release part of an account's locked balance (admin only)
+    pub fn unlock(env: Env, addr: Address, amount: i128) {
+        check_admin(&env);
+
+        let locked = read_locked(&env, addr.clone());
+        if amount <= 0 || amount > locked {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+        write_locked(&env, addr.clone(), locked - amount);
+
+        env.events().publish(
+            ("unlock",), 
+            (addr, amount)
+        );
+    }
+
+    /// Get the locked portion of an account's balance
+    pub fn locked_balance(env: Env, addr: Address) -> i128 {
+        read_locked(&env, addr)
+    }
+
     /// Globally freeze all token operations (admin only)
     pub fn set_frozen(env: Env, frozen: bool) {
         check_admin(&env);
         let mut state = read_state(&env);
         
-        if !state.is_freezable {
-            panic!("token is not freezable");
+        // Lifting a freeze is always allowed so funds can't be stranded
+        if frozen && !state.is_freezable {
+            panic_with_error!(&env, TokenError::NotFreezable);
         }
         
         state.is_frozen = frozen;
+        let guardian = state.guardian_contract.clone();
         write_state(&env, state);
+
+        // A global freeze is reported to the guardian as the token's own address
+        if let (true, Some(guardian)) = (frozen, guardian) {
+            notify_guardian(&env, &guardian, &env.current_contract_address());
+        }
         
         env.events().publish(
             ("set_frozen",), 
@@ -156,137 +473,1324 @@ impl TokenContract {
         );
     }
 
-    /// Transfer admin rights to a new address (admin only)
-    pub fn set_admin(env: Env, new_admin: Address) {
+    /// Permanently disable freezing; existing freezes can still be lifted (admin only)
+    pub fn disable_freezing(env: Env) {
         check_admin(&env);
-        write_administrator(&env, new_admin.clone());
-        
         let mut state = read_state(&env);
-        state.admin = new_admin.clone();
+        state.is_freezable = false;
         write_state(&env, state);
-        
+
         env.events().publish(
-            ("set_admin",), 
-            new_admin
+            ("disable_freezing",), 
+            ()
         );
     }
 
-    /// Get current admin address
-    pub fn admin(env: Env) -> Address {
-        read_administrator(&env)
-    }
+    /// Permanently end minting; unlike a pause this cannot be undone (admin only)
+    pub fn renounce_minting(env: Env) {
+        check_admin(&env);
+        let mut state = read_state(&env);
 
-    /// Check if an account is frozen
-    pub fn is_frozen(env: Env, addr: Address) -> bool {
-        let state = read_state(&env);
-        if state.is_frozen {
-            return true; // Globally frozen
+        if !state.is_mintable {
+            panic_with_error!(&env, TokenError::NotMintable);
         }
-        
-        // Check if specific account is frozen
-        env.storage().persistent().has(&addr)
-    }
-
-    // SEP-41 Standard Token Interface
-
-    /// Get allowance for spender from owner
-    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        read_allowance(&env, from, spender).amount
-    }
-
-    /// Approve spender to spend amount from caller's account
-    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
-        from.require_auth();
 
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        state.is_mintable = false;
+        write_state(&env, state);
 
-        write_allowance(&env, from.clone(), spender.clone(), amount, expiration_ledger);
         env.events().publish(
-            ("approve",), 
-            (from, spender, amount, expiration_ledger)
+            ("renounce_minting",), 
+            ()
         );
     }
 
-    /// Get balance of an address
-    pub fn balance(env: Env, id: Address) -> i128 {
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        read_balance(&env, id)
-    }
-
-    /// Transfer tokens from caller to another address
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
-        from.require_auth();
+    /// Change the supply cap, which may not fall below the current supply (admin only)
+    pub fn set_max_supply(env: Env, max_supply: Option<i128>) {
+        check_admin(&env);
+        let mut state = read_state(&env);
 
-        let state = read_state(&env);
-        if state.is_frozen {
-            panic!("token is globally frozen");
+        // With minting renounced the cap is final
+        if !state.is_mintable {
+            panic_with_error!(&env, TokenError::NotMintable);
         }
 
-        if Self::is_frozen(env.clone(), from.clone()) {
-            panic!("from account is frozen");
+        if matches!(max_supply, Some(max) if max <= 0) {
+            panic_with_error!(&env, TokenError::InvalidConfig);
         }
 
-        if Self::is_frozen(env.clone(), to.clone()) {
-            panic!("to account is frozen");
+        if matches!(max_supply, Some(max) if max < state.total_supply) {
+            panic_with_error!(&env, TokenError::ExceedsMaxSupply);
         }
 
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        state.max_supply = max_supply;
+        write_state(&env, state);
 
-        spend_balance(&env, from.clone(), amount);
-        receive_balance(&env, to.clone(), amount);
         env.events().publish(
-            ("transfer",), 
-            (from, to, amount)
+            ("set_max_supply",), 
+            max_supply
         );
     }
 
-    /// Transfer tokens from one address to another using allowance
-    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
-        spender.require_auth();
+    /// Set the guardian contract notified via `on_freeze(addr)` on every freeze (admin only)
+    pub fn set_guardian(env: Env, guardian: Option<Address>) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.guardian_contract = guardian.clone();
+        write_state(&env, state);
 
-        let state = read_state(&env);
-        if state.is_frozen {
-            panic!("token is globally frozen");
-        }
+        env.events().publish(
+            ("set_guardian",), 
+            guardian
+        );
+    }
 
-        if Self::is_frozen(env.clone(), from.clone()) {
-            panic!("from account is frozen");
-        }
+    /// Enable reversible transfers with a challenge window and optional arbiter (admin only)
+    pub fn set_reversal_window(env: Env, window_ledgers: u32, arbiter: Option<Address>) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.reversal_window_ledgers = window_ledgers;
+        state.arbiter = arbiter.clone();
+        write_state(&env, state);
 
-        if Self::is_frozen(env.clone(), to.clone()) {
-            panic!("to account is frozen");
-        }
+        env.events().publish(
+            ("set_reversal_window",), 
+            (window_ledgers, arbiter)
+        );
+    }
 
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    /// Require both parties of transfers and mints to be allowlisted (admin only)
+    pub fn set_allowlist_enabled(env: Env, enabled: bool) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.allowlist_enabled = enabled;
+        write_state(&env, state);
 
-        spend_allowance(&env, from.clone(), spender, amount);
-        spend_balance(&env, from.clone(), amount);
-        receive_balance(&env, to.clone(), amount);
         env.events().publish(
-            ("transfer",), 
-            (from, to, amount)
+            ("set_allowlist_enabled",), 
+            enabled
         );
     }
 
-    /// Get token decimals
-    pub fn decimals(env: Env) -> u32 {
-        read_decimal(&env)
-    }
+    /// Add an address to the allowlist (admin only)
+    pub fn add_to_allowlist(env: Env, addr: Address) {
+        check_admin(&env);
+        write_allowlisted(&env, &addr, true);
 
-    /// Get token name
-    pub fn name(env: Env) -> String {
-        read_name(&env)
+        env.events().publish(
+            ("add_to_allowlist",), 
+            addr
+        );
+    }
+
+    /// Remove an address from the allowlist (admin only)
+    pub fn remove_from_allowlist(env: Env, addr: Address) {
+        check_admin(&env);
+        write_allowlisted(&env, &addr, false);
+
+        env.events().publish(
+            ("remove_from_allowlist",), 
+            addr
+        );
+    }
+
+    /// Check if an address is on the allowlist
+    pub fn is_allowed(env: Env, addr: Address) -> bool {
+        is_allowlisted(&env, &addr)
+    }
+
+    /// Require recipients of transfers and mints to have opted in (admin only)
+    pub fn set_require_opt_in(env: Env, enabled: bool) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.require_opt_in = enabled;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_require_opt_in",), 
+            enabled
+        );
+    }
+
+    /// Opt in to receiving this token
+    pub fn opt_in(env: Env, addr: Address) {
+        addr.require_auth();
+        write_opted_in(&env, &addr);
+
+        env.events().publish(
+            ("opt_in",), 
+            addr
+        );
+    }
+
+    /// Check if an address has opted in to receiving this token
+    pub fn is_opted_in(env: Env, addr: Address) -> bool {
+        is_opted_in(&env, &addr)
+    }
+
+    /// Exclude an account, e.g. a treasury, from circulating supply (admin only)
+    pub fn set_excluded_from_circulation(env: Env, addr: Address, excluded: bool) {
+        check_admin(&env);
+        write_excluded(&env, &addr, excluded);
+
+        env.events().publish(
+            ("set_excluded_from_circulation",), 
+            (addr, excluded)
+        );
+    }
+
+    /// Enable or disable the on_token_received hook for contract recipients (admin only)
+    pub fn set_hooks_enabled(env: Env, enabled: bool) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.hooks_enabled = enabled;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_hooks_enabled",), 
+            enabled
+        );
+    }
+
+    /// Update the name and/or symbol in one call, leaving `None` fields and the
+    /// decimals as they are (admin only)
+    pub fn set_metadata(env: Env, name: Option<String>, symbol: Option<String>) {
+        check_admin(&env);
+        let mut metadata =
+            read_metadata(&env).unwrap_or_else(|| panic_with_error!(&env, TokenError::NotInitialized));
+
+        if let Some(name) = name {
+            metadata.name = name;
+        }
+        if let Some(symbol) = symbol {
+            metadata.symbol = symbol;
+        }
+        validate_metadata(&env, &metadata.name, &metadata.symbol);
+        write_metadata(&env, metadata.clone());
+
+        env.events().publish(
+            ("set_metadata",), 
+            (metadata.name, metadata.symbol)
+        );
+    }
+
+    /// Transfer admin rights to a new address (admin only)
+    pub fn set_admin(env: Env, new_admin: Address) {
+        let admin = check_admin(&env);
+        let mut state = read_state(&env);
+
+        // With a timelock configured, handoffs must go through propose_admin
+        if read_admin_timelock(&env) > 0 {
+            panic_with_error!(&env, TokenError::InvalidConfig);
+        }
+
+        write_administrator(&env, new_admin.clone());
+        state.admin = new_admin.clone();
+        write_state(&env, state);
+        
+        env.events().publish(
+            ("set_admin",), 
+            (admin, new_admin)
+        );
+    }
+
+    /// Set the ledgers a proposed admin must wait before `accept_admin`; a lower value
+    /// only takes effect once the current timelock has passed (admin only)
+    pub fn set_admin_timelock(env: Env, ledgers: u32) {
+        check_admin(&env);
+        let current = read_admin_timelock(&env);
+        let mut state = read_state(&env);
+
+        let ready_at = if ledgers >= current {
+            state.admin_timelock_ledgers = ledgers;
+            write_pending_admin_timelock(&env, None);
+            env.ledger().sequence()
+        } else {
+            // Lowering waits out the current timelock, so it cannot skip a handoff's wait
+            state.admin_timelock_ledgers = current;
+            let ready_at = env.ledger().sequence().saturating_add(current);
+            write_pending_admin_timelock(&env, Some(PendingTimelock { ledgers, ready_at }));
+            ready_at
+        };
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_admin_timelock",), 
+            (ledgers, ready_at)
+        );
+    }
+
+    /// Start handing admin rights to `new_admin`, replacing any pending handoff (admin only)
+    pub fn propose_admin(env: Env, new_admin: Address) {
+        let admin = check_admin(&env);
+        let ready_at = env
+            .ledger()
+            .sequence()
+            .saturating_add(read_admin_timelock(&env));
+        write_pending_admin(
+            &env,
+            Some(PendingAdmin {
+                admin: new_admin.clone(),
+                ready_at,
+            }),
+        );
+
+        env.events().publish(
+            ("propose_admin",), 
+            (admin, new_admin, ready_at)
+        );
+    }
+
+    /// Complete a proposed handoff once its timelock has elapsed (proposed admin only)
+    pub fn accept_admin(env: Env) {
+        let pending = read_pending_admin(&env)
+            .unwrap_or_else(|| panic_with_error!(&env, TokenError::ProposalNotFound));
+        pending.admin.require_auth();
+
+        if env.ledger().sequence() < pending.ready_at {
+            panic_with_error!(&env, GuardError::AdminTimelockActive);
+        }
+
+        let previous = read_administrator(&env);
+        write_administrator(&env, pending.admin.clone());
+        write_pending_admin(&env, None);
+
+        let mut state = read_state(&env);
+        state.admin = pending.admin.clone();
+        write_state(&env, state);
+
+        env.events().publish(
+            ("accept_admin",), 
+            (previous, pending.admin)
+        );
+    }
+
+    /// Get the pending admin handoff, if any
+    pub fn pending_admin(env: Env) -> Option<PendingAdmin> {
+        read_pending_admin(&env)
+    }
+
+    /// Hand admin rights to an N-of-M admin set; an empty set with a zero threshold
+    /// returns control to the single admin (admin only). While the set is configured
+    /// only the `AdminAction`s can run, and every other admin call is rejected
+    pub fn set_admins(env: Env, admins: Vec<Address>, threshold: u32) {
+        check_admin(&env);
+
+        if admins.is_empty() && threshold == 0 {
+            write_admin_set(&env, None);
+        } else {
+            if threshold == 0 || threshold > admins.len() {
+                panic_with_error!(&env, TokenError::InvalidConfig);
+            }
+            for (i, admin) in admins.iter().enumerate() {
+                if admins.last_index_of(&admin) != Some(i as u32) {
+                    panic_with_error!(&env, TokenError::InvalidConfig);
+                }
+            }
+            write_admin_set(&env, Some(AdminSet { admins: admins.clone(), threshold }));
+        }
+
+        env.events().publish(
+            ("set_admins",), 
+            (admins, threshold)
+        );
+    }
+
+    /// Propose an admin action, counting as the proposer's approval; returns its hash
+    pub fn propose_action(env: Env, proposer: Address, action: AdminAction) -> BytesN<32> {
+        let hash = action_hash(&env, &action);
+        if read_proposal(&env, &hash).is_none() {
+            write_proposal(&env, &hash, &Proposal { action, approvals: Vec::new(&env) });
+        }
+        Self::approve_action(env, proposer, hash.clone());
+        hash
+    }
+
+    /// Approve a proposed admin action, executing it once the threshold is reached
+    pub fn approve_action(env: Env, approver: Address, hash: BytesN<32>) {
+        approver.require_auth();
+        let admin_set = read_admin_set(&env).unwrap_or_else(|| panic_with_error!(&env, TokenError::NotAnAdmin));
+        if !admin_set.admins.contains(&approver) {
+            panic_with_error!(&env, TokenError::NotAnAdmin);
+        }
+
+        let mut proposal = read_proposal(&env, &hash)
+            .unwrap_or_else(|| panic_with_error!(&env, TokenError::ProposalNotFound));
+        if proposal.approvals.contains(&approver) {
+            panic_with_error!(&env, TokenError::AlreadyApproved);
+        }
+
+        // Approvals from members a later SetAdmins removed no longer count
+        let mut approvals = Vec::new(&env);
+        for member in proposal.approvals.iter() {
+            if admin_set.admins.contains(&member) {
+                approvals.push_back(member);
+            }
+        }
+        approvals.push_back(approver.clone());
+        proposal.approvals = approvals;
+
+        env.events().publish(
+            ("approve_action",), 
+            (approver.clone(), hash.clone())
+        );
+
+        if proposal.approvals.len() < admin_set.threshold {
+            write_proposal(&env, &hash, &proposal);
+            return;
+        }
+
+        remove_proposal(&env, &hash);
+        write_executing_admin(&env, Some(&approver));
+        match proposal.action {
+            AdminAction::Mint(to, amount) => {
+                Self::mint(env.clone(), to, amount);
+            }
+            AdminAction::SetAdmin(new_admin) => Self::set_admin(env.clone(), new_admin),
+            AdminAction::Freeze(addr, reason) => Self::freeze(env.clone(), addr, reason),
+            AdminAction::Unfreeze(addr) => Self::unfreeze(env.clone(), addr),
+            AdminAction::SetFrozen(frozen) => Self::set_frozen(env.clone(), frozen),
+            AdminAction::SetAdmins(admins, threshold) => Self::set_admins(env.clone(), admins, threshold),
+        }
+        write_executing_admin(&env, None);
+
+        env.events().publish(
+            ("execute_action",), 
+            hash
+        );
+    }
+
+    /// Get the configured admin set, if any
+    pub fn admin_set(env: Env) -> Option<AdminSet> {
+        read_admin_set(&env)
+    }
+
+    /// Get a pending proposal by its action hash
+    pub fn proposal(env: Env, hash: BytesN<32>) -> Option<Proposal> {
+        read_proposal(&env, &hash)
+    }
+
+    /// Cap the total each account may send within any `window_ledgers` span (admin only)
+    pub fn set_rolling_cap(env: Env, max: Option<i128>, window_ledgers: u32) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+
+        if let Some(max) = max {
+            if max < 0 {
+                panic_with_error!(&env, TokenError::InvalidConfig);
+            }
+            if window_ledgers == 0 {
+                panic_with_error!(&env, TokenError::InvalidConfig);
+            }
+        }
+
+        state.rolling_cap = max;
+        state.rolling_window_ledgers = window_ledgers;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_rolling_cap",), 
+            (max, window_ledgers)
+        );
+    }
+
+    /// Set the transfer fee in basis points and the address it is routed to (admin only)
+    pub fn set_transfer_fee(env: Env, fee_bps: u32, fee_collector: Option<Address>) {
+        check_admin(&env);
+        validate_fee(&env, fee_bps, &fee_collector);
+
+        let mut state = read_state(&env);
+        state.fee_bps = fee_bps;
+        state.fee_collector = fee_collector.clone();
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_transfer_fee",), 
+            (fee_bps, fee_collector)
+        );
+    }
+
+    /// Choose how fractional transfer fees are rounded (admin only)
+    pub fn set_fee_rounding(env: Env, rounding: FeeRounding) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.fee_rounding = rounding;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_fee_rounding",), 
+            rounding
+        );
+    }
+
+    /// Record a balance snapshot at the current ledger, returning its id (admin only)
+    pub fn snapshot(env: Env) -> u64 {
+        check_admin(&env);
+        let id = create_snapshot(&env);
+
+        env.events().publish(
+            ("snapshot",), 
+            (id, env.ledger().sequence())
+        );
+        id
+    }
+
+    /// Get the ledger a snapshot was taken at
+    pub fn snapshot_ledger(env: Env, snapshot_id: u64) -> u32 {
+        read_snapshot_ledger(&env, snapshot_id).unwrap_or_else(|| panic_with_error!(&env, TokenError::UnknownSnapshot))
+    }
+
+    /// Get the balance of an address as of a snapshot
+    pub fn balance_at(env: Env, id: Address, snapshot_id: u64) -> i128 {
+        if snapshot_id == 0 || snapshot_id > read_snapshot_id(&env) {
+            panic_with_error!(&env, TokenError::UnknownSnapshot);
+        }
+        read_balance_at(&env, id.clone(), snapshot_id).unwrap_or_else(|| read_balance(&env, id))
+    }
+
+    /// Require tokens to be held for `ledgers` after they were last received before burning (admin only)
+    pub fn set_min_burn_holding_period(env: Env, ledgers: u32) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.min_burn_holding_period = ledgers;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_min_burn_holding_period",), 
+            ledgers
+        );
+    }
+
+    /// Reject transfers and mints whose recipient is the token contract itself (admin only)
+    pub fn set_reject_contract_recipient(env: Env, enabled: bool) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.reject_contract_recipient = enabled;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_reject_contract_recipient",), 
+            enabled
+        );
+    }
+
+    /// Reject mints to the token contract while still accepting transfers to it (admin only)
+    pub fn set_reject_mint_to_contract(env: Env, enabled: bool) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.reject_mint_to_contract = enabled;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_reject_mint_to_contract",), 
+            enabled
+        );
+    }
+
+    /// Reject zero-amount transfers instead of silently ignoring them (admin only)
+    pub fn set_reject_zero_transfers(env: Env, enabled: bool) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.reject_zero_transfers = enabled;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_reject_zero_transfers",), 
+            enabled
+        );
+    }
+
+    /// Limit how much can be minted within each `epoch_ledgers` span (admin only)
+    pub fn set_mint_limit(env: Env, limit: Option<i128>, epoch_ledgers: u32) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+
+        if let Some(limit) = limit {
+            if limit < 0 {
+                panic_with_error!(&env, TokenError::InvalidConfig);
+            }
+            if epoch_ledgers == 0 {
+                panic_with_error!(&env, TokenError::InvalidConfig);
+            }
+        }
+
+        state.mint_limit_per_epoch = limit;
+        state.epoch_ledgers = epoch_ledgers;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_mint_limit",), 
+            (limit, epoch_ledgers)
+        );
+    }
+
+    /// Cap the number of transfers accepted within a single ledger (admin only)
+    pub fn set_max_transfers_per_ledger(env: Env, max: Option<u32>) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.max_transfers_per_ledger = max;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_max_transfers_per_ledger",), 
+            max
+        );
+    }
+
+    /// Cap the amount a single transfer may move; the admin is exempt (admin only)
+    pub fn set_max_transfer_amount(env: Env, max: Option<i128>) {
+        check_admin(&env);
+
+        if matches!(max, Some(max) if max <= 0) {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        let mut state = read_state(&env);
+        state.max_transfer_amount = max;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_max_transfer_amount",), 
+            max
+        );
+    }
+
+    /// Reject dust transfers below `min`; zero-amount transfers follow
+    /// `set_reject_zero_transfers` instead (admin only)
+    pub fn set_min_transfer_amount(env: Env, min: Option<i128>) {
+        check_admin(&env);
+
+        if matches!(min, Some(min) if min <= 0) {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        let mut state = read_state(&env);
+        state.min_transfer_amount = min;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_min_transfer_amount",), 
+            min
+        );
+    }
+
+    /// Cap the balance any non-exempt account may hold (admin only)
+    pub fn set_max_wallet_balance(env: Env, max: Option<i128>) {
+        check_admin(&env);
+
+        if matches!(max, Some(max) if max <= 0) {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        let mut state = read_state(&env);
+        state.max_wallet_balance = max;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_max_wallet_balance",), 
+            max
+        );
+    }
+
+    /// Exempt an account such as a treasury or exchange from the wallet cap (admin only)
+    pub fn set_wallet_limit_exempt(env: Env, addr: Address, exempt: bool) {
+        check_admin(&env);
+        write_wallet_limit_exempt(&env, &addr, exempt);
+
+        env.events().publish(
+            ("set_wallet_limit_exempt",), 
+            (addr, exempt)
+        );
+    }
+
+    /// Require accounts to wait this many ledgers between transfers; 0 disables it (admin only)
+    pub fn set_transfer_cooldown(env: Env, ledgers: u32) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+        state.transfer_cooldown_ledgers = ledgers;
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_transfer_cooldown",), 
+            ledgers
+        );
+    }
+
+    /// Exempt an account from the transfer cooldown (admin only)
+    pub fn set_cooldown_exempt(env: Env, addr: Address, exempt: bool) {
+        check_admin(&env);
+        write_cooldown_exempt(&env, &addr, exempt);
+
+        env.events().publish(
+            ("set_cooldown_exempt",), 
+            (addr, exempt)
+        );
+    }
+
+    /// Replace the transfer validation rules, evaluated in order (admin only)
+    pub fn set_rules(env: Env, rules: Vec<Rule>) {
+        check_admin(&env);
+        write_rules(&env, &rules);
+
+        env.events().publish(
+            ("set_rules",), 
+            rules.len()
+        );
+    }
+
+    /// Check whether the token has been initialized
+    pub fn is_initialized(env: Env) -> bool {
+        has_administrator(&env)
+    }
+
+    /// Move another asset that was sent to this contract's address out to `to` (admin only)
+    pub fn rescue_asset(env: Env, token: Address, to: Address, amount: i128) {
+        check_admin(&env);
+
+        // This token's own reserve backs tranches, vesting and pending transfers,
+        // and the underlying asset backs the wrapped supply
+        if token == env.current_contract_address() || Some(token.clone()) == read_state(&env).underlying_asset {
+            panic_with_error!(&env, TokenError::CannotRescueOwnToken);
+        }
+
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+
+        env.events().publish(
+            ("rescue_asset",), 
+            (token, to, amount)
+        );
+    }
+
+    /// Set the Stellar Asset Contract this token wraps 1:1 via `deposit`/`withdraw`,
+    /// before any supply exists; it cannot be changed once set, and `mint` is disabled
+    /// from then on (admin only)
+    pub fn set_underlying_asset(env: Env, asset: Address) {
+        check_admin(&env);
+        let mut state = read_state(&env);
+
+        if state.underlying_asset.is_some() {
+            panic_with_error!(&env, TokenError::InvalidConfig);
+        }
+
+        if state.total_supply != 0 {
+            panic_with_error!(&env, GuardError::UnbackedSupply);
+        }
+
+        state.underlying_asset = Some(asset.clone());
+        write_state(&env, state);
+
+        env.events().publish(
+            ("set_underlying_asset",), 
+            asset
+        );
+    }
+
+    /// Deposit `amount` of the underlying asset and mint the same amount of this token,
+    /// subject to every check and limit of `mint`; the mint event names this contract as minter
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        let state = read_state(&env);
+        let asset = Self::underlying_asset(&env, &state, amount);
+
+        if is_account_frozen(&env, &from) {
+            panic_with_error!(&env, TokenError::Frozen);
+        }
+
+        token::Client::new(&env, &asset).transfer(&from, &env.current_contract_address(), &amount);
+        Self::mint_tokens(&env, env.current_contract_address(), from, amount);
+    }
+
+    /// Burn `amount` of this token and send the same amount of the underlying asset to `to`
+    pub fn withdraw(env: Env, to: Address, amount: i128) {
+        to.require_auth();
+        let mut state = read_state(&env);
+        let asset = Self::underlying_asset(&env, &state, amount);
+
+        if is_account_frozen(&env, &to) {
+            panic_with_error!(&env, TokenError::Frozen);
+        }
+
+        spend_balance(&env, to.clone(), amount);
+        state.total_supply -= amount;
+        state.total_burned += amount;
+        record_supply_change(&env, -amount, state.total_supply);
+        write_state(&env, state);
+
+        token::Client::new(&env, &asset).transfer(&env.current_contract_address(), &to, &amount);
+
+        env.events().publish(
+            ("burn", to), 
+            amount
+        );
+    }
+
+    /// Get current admin address
+    pub fn admin(env: Env) -> Address {
+        read_administrator(&env)
+    }
+
+    /// Get the admin address as of a past ledger
+    pub fn admin_at(env: Env, ledger: u32) -> Address {
+        read_administrator_at(&env, ledger).unwrap_or_else(|| panic_with_error!(&env, TokenError::NoAdminAtLedger))
+    }
+
+    /// Get the sha256 of an address's XDR encoding, for keying accounts off-chain
+    pub fn account_hash(env: Env, addr: Address) -> BytesN<32> {
+        env.crypto().sha256(&addr.to_xdr(&env)).into()
+    }
+
+    /// Get the reason recorded when an account was frozen
+    pub fn freeze_reason(env: Env, addr: Address) -> Option<String> {
+        read_freeze_reason(&env, &addr)
+    }
+
+    /// Get up to `limit` frozen accounts, starting at index `start` of the frozen list
+    pub fn frozen_accounts_page(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        read_frozen_page(&env, start, limit)
+    }
+
+    /// Check if an account is frozen
+    pub fn is_frozen(env: Env, addr: Address) -> bool {
+        let state = read_state(&env);
+        if state.is_frozen {
+            return true; // Globally frozen
+        }
+        
+        is_account_frozen(&env, &addr)
+    }
+
+    /// Check whether an account is frozen and why; a global freeze takes precedence
+    pub fn frozen_status(env: Env, addr: Address) -> FrozenStatus {
+        if read_state(&env).is_frozen {
+            FrozenStatus::GloballyFrozen
+        } else if is_account_frozen(&env, &addr) {
+            FrozenStatus::AccountFrozen(addr)
+        } else {
+            FrozenStatus::NotFrozen
+        }
+    }
+
+    /// Check if any emergency state is active (currently the global freeze)
+    pub fn is_emergency(env: Env) -> bool {
+        read_state(&env).is_frozen
+    }
+
+    // SEP-41 Standard Token Interface
+
+    /// Get allowance for spender from owner
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        read_allowance(&env, from, spender).amount
+    }
+
+    /// Get allowance amount and expiration ledger for spender from owner
+    pub fn allowance_detail(env: Env, from: Address, spender: Address) -> (i128, u32) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let allowance = read_allowance(&env, from, spender);
+        (allowance.amount, allowance.expiration_ledger)
+    }
+
+    /// Remove an expired allowance entry to reclaim its storage (anyone may call)
+    pub fn prune_allowance(env: Env, from: Address, spender: Address) {
+        if prune_expired_allowance(&env, from.clone(), spender.clone()) {
+            env.events().publish(
+                ("prune_allowance",), 
+                (from, spender)
+            );
+        }
+    }
+
+    /// Prune every expired allowance among `pairs`, skipping live ones, and return how
+    /// many were removed (anyone may call)
+    pub fn prune_allowances_batch(env: Env, pairs: Vec<(Address, Address)>) -> u32 {
+        let mut pruned = 0;
+        for (from, spender) in pairs.iter() {
+            if prune_expired_allowance(&env, from.clone(), spender.clone()) {
+                env.events().publish(
+                    ("prune_allowance",), 
+                    (from, spender)
+                );
+                pruned += 1;
+            }
+        }
+        pruned
+    }
+
+    /// Approve spender to spend amount from caller's account; `i128::MAX` approves an
+    /// infinite allowance that `transfer_from` never decrements, and at most 50 spenders
+    /// may hold a live allowance from one owner at a time
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+
+        // An already-expired approval would be dead on arrival; only revocations may use one
+        if amount != 0 && expiration_ledger < env.ledger().sequence() {
+            panic_with_error!(&env, TokenError::InvalidExpiration);
+        }
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        write_allowance(&env, from.clone(), spender.clone(), amount, expiration_ledger);
+        env.events().publish(
+            ("approve",), 
+            (from, spender, amount, expiration_ledger)
+        );
+    }
+
+    /// Revoke every allowance `from` has granted, returning how many were revoked
+    pub fn revoke_all_allowances(env: Env, from: Address) -> u32 {
+        from.require_auth();
+
+        let spenders = read_allowance_spenders(&env, &from);
+        for spender in spenders.iter() {
+            write_allowance(&env, from.clone(), spender.clone(), 0, 0);
+            env.events().publish(
+                ("approve",), 
+                (from.clone(), spender, 0i128, 0u32)
+            );
+        }
+        spenders.len()
+    }
+
+    /// Approve spender on the owner's behalf from an authorization the owner signed
+    /// for exactly these arguments, so a relayer can submit it; any account type can sign
+    pub fn permit(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+    ) {
+        owner.require_auth_for_args((spender.clone(), amount, expiration_ledger, nonce).into_val(&env));
+
+        if expiration_ledger < env.ledger().sequence() {
+            panic_with_error!(&env, TokenError::PermitExpired);
+        }
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        if nonce != read_nonce(&env, owner.clone()) {
+            panic_with_error!(&env, TokenError::InvalidNonce);
+        }
+        increment_nonce(&env, owner.clone());
+
+        write_allowance(&env, owner.clone(), spender.clone(), amount, expiration_ledger);
+        env.events().publish(
+            ("approve",), 
+            (owner, spender, amount, expiration_ledger)
+        );
+    }
+
+    /// Get the next permit nonce expected for an address
+    pub fn nonce(env: Env, addr: Address) -> u64 {
+        read_nonce(&env, addr)
+    }
+
+    /// Approve or revoke an operator allowed to transfer any amount on the owner's behalf
+    pub fn set_operator(env: Env, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        write_operator(&env, owner.clone(), operator.clone(), approved);
+        env.events().publish(
+            ("set_operator",), 
+            (owner, operator, approved)
+        );
+    }
+
+    /// Check if operator is approved to manage the owner's tokens
+    pub fn is_operator(env: Env, owner: Address, operator: Address) -> bool {
+        is_operator(&env, owner, operator)
+    }
+
+    /// Get balance of an address
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        read_balance(&env, id)
+    }
+
+    /// Get an account balance without extending any TTL, for read-only simulation
+    pub fn balance_view(env: Env, id: Address) -> i128 {
+        peek_balance(&env, id)
+    }
+
+    /// Get the balances of several accounts, in the order given
+    pub fn balances_of(env: Env, ids: Vec<Address>) -> Vec<i128> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let mut balances = Vec::new(&env);
+        for id in ids.iter() {
+            balances.push_back(read_balance(&env, id));
+        }
+        balances
+    }
+
+    /// Transfer tokens from caller to another address
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+
+        if amount == 0 && Self::skip_zero_transfer(&env) {
+            return;
+        }
+
+        let received = Self::transfer_balance(&env, &from, &to, amount);
+        env.events().publish(
+            ("transfer", from.clone(), to.clone()), 
+            received
+        );
+        Self::notify_receiver(&env, &from, &to, received);
+    }
+
+    /// Transfer tokens with a memo attached to the event, e.g. an exchange deposit tag
+    pub fn transfer_memo(env: Env, from: Address, to: Address, amount: i128, memo: String) {
+        from.require_auth();
+
+        if amount == 0 && Self::skip_zero_transfer(&env) {
+            return;
+        }
+
+        if memo.len() > MAX_MEMO_LEN {
+            panic_with_error!(&env, TokenError::MemoTooLong);
+        }
+
+        let received = Self::transfer_balance(&env, &from, &to, amount);
+        env.events().publish(
+            ("transfer", from.clone(), to.clone(), memo), 
+            received
+        );
+        Self::notify_receiver(&env, &from, &to, received);
+    }
+
+    /// Transfer tokens with the recipient also authorizing receipt
+    pub fn transfer_dual_auth(env: Env, from: Address, to: Address, amount: i128) {
+        // A self-transfer is already covered by `from`'s auth in `transfer`
+        if to != from {
+            to.require_auth();
+        }
+        Self::transfer(env, from, to, amount);
+    }
+
+    /// Transfer tokens from one address to another using allowance
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+
+        if amount == 0 && Self::skip_zero_transfer(&env) {
+            return;
+        }
+
+        // A frozen spender cannot move tokens on anyone's behalf, even with an allowance
+        if is_account_frozen(&env, &spender) {
+            panic_with_error!(&env, TokenError::Frozen);
+        }
+
+        // Approved operators may move any amount without an allowance
+        if !is_operator(&env, from.clone(), spender.clone()) {
+            spend_allowance(&env, from.clone(), spender.clone(), amount);
+            let remaining = read_allowance(&env, from.clone(), spender.clone());
+            env.events().publish(
+                ("approve",), 
+                (from.clone(), spender, remaining.amount, remaining.expiration_ledger)
+            );
+        }
+
+        let received = Self::transfer_balance(&env, &from, &to, amount);
+        env.events().publish(
+            ("transfer", from.clone(), to.clone()), 
+            received
+        );
+        Self::notify_receiver(&env, &from, &to, received);
+    }
+
+    /// Transfer `total`, less any transfer fee, into a vesting schedule that releases
+    /// it to `to` in `tranches` parts every `interval_ledgers`, returning the schedule id
+    pub fn transfer_tranched(
+        env: Env,
+        from: Address,
+        to: Address,
+        total: i128,
+        tranches: u32,
+        interval_ledgers: u32,
+    ) -> u64 {
+        from.require_auth();
+
+        if tranches == 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        if total <= 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        // Tranched tokens are held by the contract until claimed
+        let held = Self::escrow_balance(&env, &from, &to, total);
+
+        let id = next_tranche_id(&env);
+        let schedule = TrancheSchedule {
+            beneficiary: to.clone(),
+            total: held,
+            tranches,
+            interval_ledgers,
+            start_ledger: env.ledger().sequence(),
+            claimed: 0,
+        };
+        write_tranche(&env, id, &schedule);
+
+        env.events().publish(
+            ("transfer_tranched",), 
+            (from, to, held, id)
+        );
+        id
+    }
+
+    /// Claim all tranches released so far for a schedule, returning the amount claimed
+    pub fn claim_tranches(env: Env, id: u64) -> i128 {
+        let mut schedule = read_tranche(&env, id).unwrap_or_else(|| panic_with_error!(&env, TokenError::TrancheNotFound));
+        schedule.beneficiary.require_auth();
+
+        let amount = tranche_unlocked(&env, &schedule) - schedule.claimed;
+        if amount > 0 {
+            Self::release_balance(&env, &schedule.beneficiary, amount);
+            schedule.claimed += amount;
+
+            if schedule.claimed == schedule.total {
+                remove_tranche(&env, id);
+            } else {
+                write_tranche(&env, id, &schedule);
+            }
+
+            env.events().publish(
+                ("claim_tranches",), 
+                (schedule.beneficiary, id, amount)
+            );
+        }
+        amount
+    }
+
+    /// Vest `total` from the admin's balance to `beneficiary`, releasing it linearly
+    /// over `duration_ledgers` from `start_ledger` (admin only)
+    pub fn create_vesting(
+        env: Env,
+        beneficiary: Address,
+        total: i128,
+        start_ledger: u32,
+        duration_ledgers: u32,
+    ) {
+        check_admin(&env);
+
+        if total <= 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        if duration_ledgers == 0 {
+            panic_with_error!(&env, TokenError::InvalidConfig);
+        }
+
+        if read_vesting(&env, beneficiary.clone()).is_some() {
+            panic_with_error!(&env, TokenError::VestingExists);
+        }
+
+        // The treasury allocation is held by the contract until claimed
+        let admin = read_administrator(&env);
+        let held = Self::escrow_balance(&env, &admin, &beneficiary, total);
+
+        let schedule = VestingSchedule {
+            total: held,
+            start_ledger,
+            duration_ledgers,
+            claimed: 0,
+        };
+        write_vesting(&env, beneficiary.clone(), &schedule);
+
+        env.events().publish(
+            ("create_vesting",), 
+            (beneficiary, held, start_ledger, duration_ledgers)
+        );
+    }
+
+    /// Claim everything vested so far, returning the amount claimed
+    pub fn claim_vested(env: Env, beneficiary: Address) -> i128 {
+        beneficiary.require_auth();
+        let mut schedule = read_vesting(&env, beneficiary.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, TokenError::VestingNotFound));
+
+        let amount = vested_amount(&env, &schedule) - schedule.claimed;
+        if amount > 0 {
+            Self::release_balance(&env, &beneficiary, amount);
+            schedule.claimed += amount;
+
+            if schedule.claimed == schedule.total {
+                remove_vesting(&env, beneficiary.clone());
+            } else {
+                write_vesting(&env, beneficiary.clone(), &schedule);
+            }
+
+            env.events().publish(
+                ("claim_vested",), 
+                (beneficiary, amount)
+            );
+        }
+        amount
+    }
+
+    /// Get the vesting schedule for a beneficiary (if it has not been fully claimed)
+    pub fn vesting_schedule(env: Env, beneficiary: Address) -> Option<VestingSchedule> {
+        read_vesting(&env, beneficiary)
+    }
+
+    /// Transfer tokens that the sender or arbiter can reverse until the challenge window
+    /// ends, returning its id; any transfer fee is charged up front and not refunded
+    pub fn transfer_reversible(env: Env, from: Address, to: Address, amount: i128) -> u64 {
+        from.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, TokenError::InvalidAmount);
+        }
+
+        let state = read_state(&env);
+        if state.reversal_window_ledgers == 0 {
+            panic_with_error!(&env, TokenError::ReversibleDisabled);
+        }
+
+        // The contract holds the tokens until the transfer finalizes or is reversed
+        let held = Self::escrow_balance(&env, &from, &to, amount);
+
+        let id = next_reversal_id(&env);
+        let pending = PendingTransfer {
+            from: from.clone(),
+            to: to.clone(),
+            amount: held,
+            finalizes_at: env.ledger().sequence().saturating_add(state.reversal_window_ledgers),
+        };
+        write_pending_transfer(&env, id, &pending);
+
+        env.events().publish(
+            ("transfer_reversible",), 
+            (from, to, held, id)
+        );
+        id
+    }
+
+    /// Deliver a reversible transfer once its challenge window has passed (anyone may call)
+    pub fn finalize_transfer(env: Env, id: u64) {
+        let pending = read_pending_transfer(&env, id).unwrap_or_else(|| panic_with_error!(&env, TokenError::PendingTransferNotFound));
+
+        if env.ledger().sequence() < pending.finalizes_at {
+            panic_with_error!(&env, TokenError::ChallengeWindowOpen);
+        }
+
+        remove_pending_transfer(&env, id);
+        Self::release_balance(&env, &pending.to, pending.amount);
+
+        env.events().publish(
+            ("transfer", pending.from, pending.to), 
+            pending.amount
+        );
+    }
+
+    /// Refund a reversible transfer to its sender before it finalizes (sender or arbiter)
+    pub fn reverse_transfer(env: Env, caller: Address, id: u64) {
+        caller.require_auth();
+        let pending = read_pending_transfer(&env, id).unwrap_or_else(|| panic_with_error!(&env, TokenError::PendingTransferNotFound));
+
+        let state = read_state(&env);
+        if caller != pending.from && Some(caller.clone()) != state.arbiter {
+            panic_with_error!(&env, TokenError::NotReversalParty);
+        }
+
+        if env.ledger().sequence() >= pending.finalizes_at {
+            panic_with_error!(&env, TokenError::ChallengeWindowClosed);
+        }
+
+        remove_pending_transfer(&env, id);
+        Self::release_balance(&env, &pending.from, pending.amount);
+
+        env.events().publish(
+            ("reverse_transfer",), 
+            (caller, pending.from, pending.to, pending.amount, id)
+        );
+    }
+
+    /// Get a reversible transfer (if it has not finalized or been reversed)
+    pub fn pending_transfer(env: Env, id: u64) -> Option<PendingTransfer> {
+        read_pending_transfer(&env, id)
+    }
+
+    /// Get a tranche schedule (if it has not been fully claimed)
+    pub fn tranche_schedule(env: Env, id: u64) -> Option<TrancheSchedule> {
+        read_tranche(&env, id)
+    }
+
+    /// Split a balance into whole units and the fractional remainder
+    pub fn balance_formatted(env: Env, id: Address) -> (i128, i128) {
+        let unit = 10i128.pow(read_decimal(&env));
+        let balance = read_balance(&env, id);
+        (balance / unit, balance % unit)
+    }
+
+    /// Amount `minter` may still mint under the epoch limit and max supply, checked as
+    /// `mint` would; with an admin set configured any of its members counts as a minter
+    pub fn minter_remaining(env: Env, minter: Address) -> i128 {
+        if !has_administrator(&env) {
+            return 0;
+        }
+
+        let is_minter = match read_admin_set(&env) {
+            Some(admin_set) => admin_set.admins.contains(&minter),
+            None => minter == read_administrator(&env),
+        };
+        if !is_minter {
+            return 0;
+        }
+
+        let state = read_state(&env);
+        if !state.is_mintable || state.is_frozen {
+            return 0;
+        }
+
+        // Without a max supply, i128::MAX is still the ceiling `mint_tokens` enforces
+        let supply_left = state.max_supply.unwrap_or(i128::MAX) - state.total_supply;
+        let epoch_left = match state.mint_limit_per_epoch {
+            Some(limit) => limit - read_epoch_minted(&env, state.epoch_ledgers),
+            None => i128::MAX,
+        };
+        supply_left.min(epoch_left).max(0)
+    }
+
+    /// Version of the event formats this contract emits, for off-chain indexers
+    pub fn event_schema_version(_env: Env) -> u32 {
+        EVENT_SCHEMA_VERSION
+    }
+
+    /// Get token name, symbol and decimals in one read
+    pub fn metadata(env: Env) -> (String, String, u32) {
+        match read_metadata(&env) {
+            Some(metadata) => (metadata.name, metadata.symbol, metadata.decimal),
+            None => (String::from_str(&env, ""), String::from_str(&env, ""), 0),
+        }
+    }
+
+    /// Get token decimals
+    pub fn decimals(env: Env) -> u32 {
+        read_decimal(&env)
+    }
+
+    /// Get token name
+    pub fn name(env: Env) -> String {
+        read_name(&env)
     }
 
     /// Get token symbol
@@ -302,6 +1806,109 @@ impl TokenContract {
         read_state(&env).total_supply
     }
 
+    /// Get the tokens burned to the burn address; supply-reducing burns are in `economics`
+    pub fn burned_supply(env: Env) -> i128 {
+        read_state(&env).burned_supply
+    }
+
+    /// Get the total supply less excluded accounts and the contract's own reserve
+    pub fn circulating_supply(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        read_circulating_supply(&env, read_state(&env).total_supply)
+    }
+
+    /// Get up to `limit` of the most recent supply changes as `(ledger, delta, new_total)`,
+    /// oldest first, starting at index `start`
+    pub fn supply_history(env: Env, start: u32, limit: u32) -> Vec<(u32, i128, i128)> {
+        page(read_supply_history(&env), start, limit)
+    }
+
+    /// Get the supply figures in one call
+    pub fn economics(env: Env) -> Economics {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let state = read_state(&env);
+
+        let inflation_bps = if state.mint_limit_per_epoch.is_some() && state.epoch_ledgers > 0 {
+            // Burns within the epoch can leave less supply than was minted in it
+            let minted = read_epoch_minted(&env, state.epoch_ledgers);
+            let supply_before = state.total_supply.checked_sub(minted).unwrap_or(0);
+            if supply_before <= 0 {
+                0
+            } else {
+                minted.saturating_mul(10_000) / supply_before
+            }
+        } else {
+            0
+        };
+
+        Economics {
+            total_supply: state.total_supply,
+            circulating_supply: read_circulating_supply(&env, state.total_supply),
+            max_supply: state.max_supply,
+            total_minted: state.total_minted,
+            total_burned: state.total_burned,
+            holder_count: read_holder_count(&env),
+            inflation_bps,
+        }
+    }
+
+    /// Get the number of addresses holding a nonzero balance
+    pub fn total_holders(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        read_holder_count(&env)
+    }
+
+    /// Get the transfer fee in basis points and its collector
+    pub fn transfer_fee(env: Env) -> (u32, Option<Address>) {
+        let state = read_state(&env);
+        (state.fee_bps, state.fee_collector)
+    }
+
+    /// Get the largest single transfer amount seen so far
+    pub fn max_transfer(env: Env) -> i128 {
+        read_max_transfer(&env)
+    }
+
+    /// Get every flag and cap from a single read of the state
+    pub fn config(env: Env) -> TokenConfig {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let state = read_state(&env);
+        TokenConfig {
+            is_mintable: state.is_mintable,
+            is_burnable: state.is_burnable,
+            is_freezable: state.is_freezable,
+            is_frozen: state.is_frozen,
+            max_supply: state.max_supply,
+            fee_bps: state.fee_bps,
+            fee_collector: state.fee_collector,
+            fee_rounding: state.fee_rounding,
+            rolling_cap: state.rolling_cap,
+            mint_limit_per_epoch: state.mint_limit_per_epoch,
+            max_transfers_per_ledger: state.max_transfers_per_ledger,
+            max_transfer_amount: state.max_transfer_amount,
+            min_transfer_amount: state.min_transfer_amount,
+            max_wallet_balance: state.max_wallet_balance,
+            reject_contract_recipient: state.reject_contract_recipient,
+            reject_mint_to_contract: state.reject_mint_to_contract,
+            reject_zero_transfers: state.reject_zero_transfers,
+            hooks_enabled: state.hooks_enabled,
+            allowlist_enabled: state.allowlist_enabled,
+            require_opt_in: state.require_opt_in,
+            force_transfer_enabled: state.force_transfer_enabled,
+            transfer_cooldown_ledgers: state.transfer_cooldown_ledgers,
+            burn_address: state.burn_address,
+        }
+    }
+
     /// Get max supply (if set)
     pub fn max_supply(env: Env) -> Option<i128> {
         env.storage()
@@ -324,4 +1931,267 @@ impl TokenContract {
     pub fn is_freezable(env: Env) -> bool {
         read_state(&env).is_freezable
     }
+}
+
+impl TokenContract {
+    /// Burn `amount` from `from` after the caller's checks, either reducing supply or,
+    /// in burn address mode, moving it to the burn address
+    fn burn_balance(env: &Env, mut state: TokenState, from: &Address, amount: i128) {
+        // A negative burn would credit `from` and grow the supply past its ceiling unchecked
+        if amount < 0 {
+            panic_with_error!(env, TokenError::InvalidAmount);
+        }
+
+        spend_balance(env, from.clone(), amount);
+        match state.burn_address.clone() {
+            Some(burn_address) => {
+                receive_balance(env, burn_address, amount);
+                state.burned_supply += amount;
+            }
+            None => {
+                state.total_supply -= amount;
+                state.total_burned += amount;
+                record_supply_change(env, -amount, state.total_supply);
+            }
+        }
+        write_state(env, state);
+
+        env.events().publish(
+            ("burn", from.clone()), 
+            amount
+        );
+    }
+
+    /// Once the token wraps an asset, only `deposit` may mint, so every unit stays backed
+    fn check_unbacked_mint(env: &Env) {
+        if read_state(env).underlying_asset.is_some() {
+            panic_with_error!(env, GuardError::UnbackedSupply);
+        }
+    }
+
+    /// A zero-amount transfer is a silent no-op, or rejected in strict mode
+    fn skip_zero_transfer(env: &Env) -> bool {
+        if read_state(env).reject_zero_transfers {
+            panic_with_error!(env, TokenError::ZeroAmount);
+        }
+        true
+    }
+
+    /// Run the receiver hook for a direct transfer when hooks are enabled
+    fn notify_receiver(env: &Env, from: &Address, to: &Address, amount: i128) {
+        if read_state(env).hooks_enabled {
+            notify_receiver(env, from, to, amount);
+        }
+    }
+
+    /// Checks shared by direct and delegated transfers; reads state but never writes it
+    fn check_transfer(
+        env: &Env,
+        state: &TokenState,
+        rules: &Vec<Rule>,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+    ) {
+        Self::check_receive(env, state, to);
+
+        if is_account_frozen(env, from) {
+            panic_with_error!(env, TokenError::Frozen);
+        }
+
+        if state.allowlist_enabled && !is_allowlisted(env, from) {
+            panic_with_error!(env, TokenError::NotAllowlisted);
+        }
+
+        if amount < 0 {
+            panic_with_error!(env, TokenError::InvalidAmount);
+        }
+
+        if let Some(min) = state.min_transfer_amount {
+            if amount > 0 && amount < min {
+                panic_with_error!(env, GuardError::BelowMinTransfer);
+            }
+        }
+
+        if let Some(max) = state.max_transfer_amount {
+            if amount > max && *from != state.admin {
+                panic_with_error!(env, TokenError::ExceedsMaxTransfer);
+            }
+        }
+
+        check_transfer_cooldown(env, state, from);
+        check_rules(env, rules, from, to, amount);
+    }
+
+    /// The recipient half of `check_transfer`, also run when escrowed tokens are delivered
+    fn check_receive(env: &Env, state: &TokenState, to: &Address) {
+        if state.is_frozen {
+            panic_with_error!(env, TokenError::GloballyFrozen);
+        }
+
+        if is_account_frozen(env, to) {
+            panic_with_error!(env, TokenError::Frozen);
+        }
+
+        if state.reject_contract_recipient && *to == env.current_contract_address() {
+            panic_with_error!(env, TokenError::ContractRecipient);
+        }
+
+        if state.allowlist_enabled && !is_allowlisted(env, to) {
+            panic_with_error!(env, TokenError::NotAllowlisted);
+        }
+
+        if state.require_opt_in && !is_opted_in(env, to) {
+            panic_with_error!(env, GuardError::NotOptedIn);
+        }
+    }
+
+    /// Validate a direct transfer, then move the balance, returning the amount
+    /// `to` received after fees
+    fn transfer_balance(env: &Env, from: &Address, to: &Address, amount: i128) -> i128 {
+        Self::move_balance(env, from, to, to, amount)
+    }
+
+    /// Validate a transfer to `to` whose tokens the contract holds until they are
+    /// delivered with `release_balance`, returning the amount held after fees
+    fn escrow_balance(env: &Env, from: &Address, to: &Address, amount: i128) -> i128 {
+        Self::move_balance(env, from, to, &env.current_contract_address(), amount)
+    }
+
+    /// Validate a transfer from `from` to `to` and credit `holder`, which is `to`
+    /// itself or the contract for escrowed transfers
+    fn move_balance(env: &Env, from: &Address, to: &Address, holder: &Address, amount: i128) -> i128 {
+        // Validate everything against a single read of the state before writing
+        let state = read_state(env);
+        let rules = read_rules(env);
+        assert_supply_invariant(env, &state);
+        Self::check_transfer(env, &state, &rules, from, to, amount);
+
+        check_spendable(env, from.clone(), amount);
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        if let Some(max) = state.max_transfers_per_ledger {
+            record_ledger_transfer(env, max);
+        }
+        record_rules_transfer(env, &rules, from);
+        record_transfer_ledger(env, &state, from);
+
+        // A self-transfer moves nothing
+        if from == holder {
+            return amount;
+        }
+
+        if let Some(max) = state.rolling_cap {
+            record_rolling_outgoing(env, from.clone(), amount, max, state.rolling_window_ledgers);
+        }
+
+        record_transfer_amount(env, amount);
+        spend_balance(env, from.clone(), amount);
+        let received = charge_transfer_fee(env, &state, from, amount);
+        // Escrowed tokens count towards the recipient's cap when they are delivered
+        if holder == to {
+            check_wallet_limit(env, &state, to, received);
+        }
+        receive_balance(env, holder.clone(), received);
+        received
+    }
+
+    /// Deliver `amount` of escrowed tokens from the contract to `to`, subject to the
+    /// recipient checks in force at delivery
+    fn release_balance(env: &Env, to: &Address, amount: i128) {
+        let state = read_state(env);
+        Self::check_receive(env, &state, to);
+        check_wallet_limit(env, &state, to, amount);
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        spend_balance(env, env.current_contract_address(), amount);
+        receive_balance(env, to.clone(), amount);
+    }
+
+    /// Mint on behalf of an already-authorized `admin`, returning the new total supply
+    fn mint_tokens(env: &Env, admin: Address, to: Address, amount: i128) -> i128 {
+        let mut state = read_state(env);
+        
+        if !state.is_mintable {
+            panic_with_error!(env, TokenError::NotMintable);
+        }
+
+        if state.is_frozen {
+            panic_with_error!(env, TokenError::GloballyFrozen);
+        }
+
+        // Tokens minted to the contract land in its reserve: they count towards total
+        // supply but not circulating supply, and only vesting claims move them out
+        let rejects_contract = state.reject_contract_recipient || state.reject_mint_to_contract;
+        if rejects_contract && to == env.current_contract_address() {
+            panic_with_error!(env, TokenError::ContractRecipient);
+        }
+
+        if state.allowlist_enabled && !is_allowlisted(env, &to) {
+            panic_with_error!(env, TokenError::NotAllowlisted);
+        }
+
+        if state.require_opt_in && !is_opted_in(env, &to) {
+            panic_with_error!(env, GuardError::NotOptedIn);
+        }
+
+        // A negative mint would shrink the supply and underflow the recipient's balance
+        if amount < 0 {
+            panic_with_error!(env, TokenError::InvalidAmount);
+        }
+
+        // i128::MAX is a hard ceiling, whether or not a max supply is configured
+        let new_supply = state
+            .total_supply
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, TokenError::Overflow));
+
+        // Check max supply constraint
+        if let Some(max_supply) = state.max_supply {
+            if new_supply > max_supply {
+                panic_with_error!(env, TokenError::ExceedsMaxSupply);
+            }
+        }
+
+        check_wallet_limit(env, &state, &to, amount);
+
+        if let Some(limit) = state.mint_limit_per_epoch {
+            record_epoch_mint(env, amount, limit, state.epoch_ledgers);
+        }
+
+        state.total_supply = new_supply;
+        state.total_minted += amount;
+        write_state(env, state);
+        record_supply_change(env, amount, new_supply);
+        
+        receive_balance(env, to.clone(), amount);
+        
+        env.events().publish(
+            ("mint", admin, to), 
+            amount
+        );
+        new_supply
+    }
+
+    /// The configured underlying asset, after the checks shared by `deposit` and `withdraw`
+    fn underlying_asset(env: &Env, state: &TokenState, amount: i128) -> Address {
+        if state.is_frozen {
+            panic_with_error!(env, TokenError::GloballyFrozen);
+        }
+
+        if amount <= 0 {
+            panic_with_error!(env, TokenError::InvalidAmount);
+        }
+
+        state
+            .underlying_asset
+            .clone()
+            .unwrap_or_else(|| panic_with_error!(env, TokenError::InvalidConfig))
+    }
 }
\ No newline at end of file
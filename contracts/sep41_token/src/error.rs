@@ -0,0 +1,73 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TokenError {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InsufficientBalance = 3,
+    InsufficientAllowance = 4,
+    NotMintable = 5,
+    NotBurnable = 6,
+    NotFreezable = 7,
+    GloballyFrozen = 8,
+    Frozen = 9,
+    ExceedsMaxSupply = 10,
+    Overflow = 11,
+    InvalidDecimals = 12,
+    InvalidMetadata = 13,
+    InvalidAmount = 14,
+    InvalidConfig = 15,
+    ContractRecipient = 16,
+    RollingCapExceeded = 17,
+    EpochMintLimitExceeded = 18,
+    ThroughputCapReached = 19,
+    HoldingPeriodNotElapsed = 20,
+    PermitExpired = 21,
+    InvalidNonce = 22,
+    InvalidPermitOwner = 23,
+    UnknownSnapshot = 24,
+    NoAdminAtLedger = 25,
+    TrancheNotFound = 26,
+    PendingTransferNotFound = 27,
+    ReversibleDisabled = 28,
+    ChallengeWindowOpen = 29,
+    ChallengeWindowClosed = 30,
+    NotReversalParty = 31,
+    RuleMinAmount = 32,
+    RuleAllowlist = 33,
+    RuleCooldown = 34,
+    MemoTooLong = 35,
+    BalanceLocked = 36,
+    VestingExists = 37,
+    VestingNotFound = 38,
+    NotAllowlisted = 39,
+    ThresholdRequired = 40,
+    NotAnAdmin = 41,
+    AlreadyApproved = 42,
+    ProposalNotFound = 43,
+    CannotRescueOwnToken = 44,
+    ZeroAmount = 45,
+    ExceedsMaxTransfer = 46,
+    ExceedsWalletLimit = 47,
+    InvalidExpiration = 48,
+    InsufficientBalanceToBurn = 49,
+    ForceTransferDisabled = 50,
+}
+
+/// Errors outside `TokenError`, which is at the 50 variant limit of a contract error enum.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum GuardError {
+    AdminTimelockActive = 52,
+    BatchLengthMismatch = 53,
+    BelowMinTransfer = 54,
+    NotOptedIn = 55,
+    SupplyInvariantViolated = 56,
+    TransferCooldown = 57,
+    TooManySpenders = 58,
+    ContractReserve = 59,
+    UnbackedSupply = 60,
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::balance::receive_balance;
+use crate::error::TokenError;
+use crate::storage_types::{FeeRounding, TokenState};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+const MAX_FEE_BPS: u32 = 10_000;
+
+pub fn validate_fee(e: &Env, fee_bps: u32, fee_collector: &Option<Address>) {
+    if fee_bps > MAX_FEE_BPS {
+        panic_with_error!(e, TokenError::InvalidConfig);
+    }
+    if fee_bps > 0 && fee_collector.is_none() {
+        panic_with_error!(e, TokenError::InvalidConfig);
+    }
+}
+
+/// The fee on `amount`, rounded per the configured mode; never more than `amount`.
+fn transfer_fee(e: &Env, state: &TokenState, amount: i128) -> i128 {
+    let scaled = amount
+        .checked_mul(state.fee_bps as i128)
+        .unwrap_or_else(|| panic_with_error!(e, TokenError::Overflow));
+    let fee = scaled / MAX_FEE_BPS as i128;
+    match state.fee_rounding {
+        FeeRounding::Up if scaled % MAX_FEE_BPS as i128 != 0 => fee + 1,
+        _ => fee,
+    }
+}
+
+/// Route the transfer fee on `amount` to the collector and return what the
+/// recipient should receive; the two always sum to `amount`.
+pub fn charge_transfer_fee(e: &Env, state: &TokenState, from: &Address, amount: i128) -> i128 {
+    let fee = transfer_fee(e, state, amount);
+    if fee > 0 {
+        if let Some(collector) = &state.fee_collector {
+            receive_balance(e, collector.clone(), fee);
+            e.events().publish(
+                ("transfer", from.clone(), collector.clone()), 
+                fee
+            );
+            return amount - fee;
+        }
+    }
+    amount
+}
@@ -0,0 +1,81 @@
+use soroban_sdk::{vec, Address, Env, String, Vec};
+
+use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+
+// Frozen accounts carry a persistent entry holding the reason they were frozen
+pub fn is_account_frozen(e: &Env, addr: &Address) -> bool {
+    e.storage().persistent().has(&DataKey::Frozen(addr.clone()))
+}
+
+pub fn read_freeze_reason(e: &Env, addr: &Address) -> Option<String> {
+    e.storage().persistent().get(&DataKey::Frozen(addr.clone()))
+}
+
+pub fn write_account_frozen(e: &Env, addr: &Address, reason: Option<String>) {
+    let key = DataKey::Frozen(addr.clone());
+    let was_frozen = e.storage().persistent().has(&key);
+    let frozen = reason.is_some();
+    match reason {
+        Some(reason) => e.storage().persistent().set(&key, &reason),
+        None => e.storage().persistent().remove(&key),
+    }
+
+    if was_frozen != frozen {
+        if frozen {
+            push_frozen(e, addr);
+        } else {
+            remove_frozen(e, addr);
+        }
+    }
+}
+
+/// Up to `limit` frozen accounts starting at index `start` of the frozen list; an
+/// unfreeze moves the most recently frozen account into the freed slot.
+pub fn read_frozen_page(e: &Env, start: u32, limit: u32) -> Vec<Address> {
+    let end = start.saturating_add(limit).min(read_frozen_count(e));
+    let mut page = vec![e];
+    for index in start.min(end)..end {
+        page.push_back(e.storage().persistent().get(&DataKey::FrozenAccount(index)).unwrap());
+    }
+    page
+}
+
+fn read_frozen_count(e: &Env) -> u32 {
+    e.storage().instance().get(&DataKey::FrozenCount).unwrap_or(0)
+}
+
+// The list is one persistent slot per account, so freezing and paging never
+// load the whole list
+fn write_frozen_slot(e: &Env, index: u32, addr: &Address) {
+    let slot = DataKey::FrozenAccount(index);
+    e.storage().persistent().set(&slot, addr);
+    e.storage()
+        .persistent()
+        .extend_ttl(&slot, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+
+    let key = DataKey::FrozenIndex(addr.clone());
+    e.storage().persistent().set(&key, &index);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+fn push_frozen(e: &Env, addr: &Address) {
+    let count = read_frozen_count(e);
+    write_frozen_slot(e, count, addr);
+    e.storage().instance().set(&DataKey::FrozenCount, &(count + 1));
+}
+
+fn remove_frozen(e: &Env, addr: &Address) {
+    let key = DataKey::FrozenIndex(addr.clone());
+    let index: u32 = e.storage().persistent().get(&key).unwrap();
+    e.storage().persistent().remove(&key);
+
+    let last = read_frozen_count(e) - 1;
+    if index != last {
+        let moved: Address = e.storage().persistent().get(&DataKey::FrozenAccount(last)).unwrap();
+        write_frozen_slot(e, index, &moved);
+    }
+    e.storage().persistent().remove(&DataKey::FrozenAccount(last));
+    e.storage().instance().set(&DataKey::FrozenCount, &last);
+}
\ No newline at end of file
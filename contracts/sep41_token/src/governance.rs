@@ -0,0 +1,55 @@
+use crate::storage_types::{
+    AdminAction, AdminSet, DataKey, Proposal, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{xdr::ToXdr, Address, BytesN, Env};
+
+pub fn read_admin_set(e: &Env) -> Option<AdminSet> {
+    let key = DataKey::AdminSet;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_admin_set(e: &Env, admin_set: Option<AdminSet>) {
+    let key = DataKey::AdminSet;
+    match admin_set {
+        Some(admin_set) => e.storage().instance().set(&key, &admin_set),
+        None => e.storage().instance().remove(&key),
+    }
+}
+
+/// Proposals are keyed by the sha256 of the action's XDR encoding.
+pub fn action_hash(e: &Env, action: &AdminAction) -> BytesN<32> {
+    e.crypto().sha256(&action.clone().to_xdr(e)).into()
+}
+
+pub fn read_proposal(e: &Env, hash: &BytesN<32>) -> Option<Proposal> {
+    let key = DataKey::Proposal(hash.clone());
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_proposal(e: &Env, hash: &BytesN<32>, proposal: &Proposal) {
+    let key = DataKey::Proposal(hash.clone());
+    e.storage().persistent().set(&key, proposal);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_proposal(e: &Env, hash: &BytesN<32>) {
+    let key = DataKey::Proposal(hash.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// The admin whose approval is currently executing an action, which stands in
+/// for the admin's authorization while an admin set is configured.
+pub fn read_executing_admin(e: &Env) -> Option<Address> {
+    let key = DataKey::ExecutingAction;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_executing_admin(e: &Env, approver: Option<&Address>) {
+    let key = DataKey::ExecutingAction;
+    match approver {
+        Some(approver) => e.storage().instance().set(&key, approver),
+        None => e.storage().instance().remove(&key),
+    }
+}
\ No newline at end of file
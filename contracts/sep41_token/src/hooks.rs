@@ -0,0 +1,38 @@
+use soroban_sdk::{vec, Address, Env, Error, IntoVal, Symbol};
+
+const STRKEY_LEN: usize = 56;
+
+/// Tell the guardian contract that `addr` was frozen. Guardian failures are
+/// ignored so a misbehaving guardian can never block a freeze.
+pub fn notify_guardian(e: &Env, guardian: &Address, addr: &Address) {
+    let _ = e.try_invoke_contract::<(), Error>(
+        guardian,
+        &Symbol::new(e, "on_freeze"),
+        vec![e, addr.into_val(e)],
+    );
+}
+
+/// Call `on_token_received(from, amount)` on a contract recipient. Recipients
+/// that don't implement the hook, or fail in it, never block the transfer.
+/// The host rejects any call back into this contract while the hook runs.
+pub fn notify_receiver(e: &Env, from: &Address, to: &Address, amount: i128) {
+    if !is_contract(to) || *to == e.current_contract_address() {
+        return;
+    }
+    let _ = e.try_invoke_contract::<(), Error>(
+        to,
+        &Symbol::new(e, "on_token_received"),
+        vec![e, from.into_val(e), amount.into_val(e)],
+    );
+}
+
+// Contract addresses encode as `C...` strkeys
+fn is_contract(addr: &Address) -> bool {
+    let strkey = addr.to_string();
+    if strkey.len() as usize != STRKEY_LEN {
+        return false;
+    }
+    let mut encoded = [0u8; STRKEY_LEN];
+    strkey.copy_into_slice(&mut encoded);
+    encoded[0] == b'C'
+}
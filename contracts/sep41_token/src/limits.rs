@@ -0,0 +1,159 @@
+use crate::admin::read_state;
+use crate::balance::read_balance;
+use crate::error::{GuardError, TokenError};
+use crate::storage_types::{DataKey, TokenState, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{panic_with_error, vec, Address, Env, Vec};
+
+/// Record an outgoing amount against `addr`'s rolling window, panicking if the
+/// amounts sent within the last `window_ledgers` would exceed `max`.
+pub fn record_rolling_outgoing(e: &Env, addr: Address, amount: i128, max: i128, window_ledgers: u32) {
+    let key = DataKey::RollingWindow(addr);
+    let entries: Vec<(u32, i128)> = e.storage().persistent().get(&key).unwrap_or(vec![e]);
+    let now = e.ledger().sequence();
+
+    let mut live = vec![e];
+    let mut spent: i128 = 0;
+    for (ledger, sent) in entries.iter() {
+        if ledger.saturating_add(window_ledgers) > now {
+            live.push_back((ledger, sent));
+            spent += sent;
+        }
+    }
+
+    if spent + amount > max {
+        panic_with_error!(e, TokenError::RollingCapExceeded);
+    }
+
+    live.push_back((now, amount));
+    e.storage().persistent().set(&key, &live);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+
+/// Remember when `addr` last received tokens, while a burn holding period is configured.
+pub fn record_receipt(e: &Env, addr: Address) {
+    if read_state(e).min_burn_holding_period == 0 {
+        return;
+    }
+    let key = DataKey::LastReceived(addr);
+    e.storage().persistent().set(&key, &e.ledger().sequence());
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn check_burn_holding_period(e: &Env, addr: Address, holding_period: u32) {
+    let key = DataKey::LastReceived(addr);
+    if let Some(received_at) = e.storage().persistent().get::<DataKey, u32>(&key) {
+        if e.ledger().sequence() < received_at.saturating_add(holding_period) {
+            panic_with_error!(e, TokenError::HoldingPeriodNotElapsed);
+        }
+    }
+}
+
+/// Record `amount` against the current mint epoch, panicking if more than
+/// `limit` would be minted within it. Epochs are fixed `epoch_ledgers` spans.
+pub fn record_epoch_mint(e: &Env, amount: i128, limit: i128, epoch_ledgers: u32) {
+    let key = DataKey::EpochMint;
+    let epoch = e.ledger().sequence() / epoch_ledgers;
+    let minted = read_epoch_minted(e, epoch_ledgers);
+
+    if minted + amount > limit {
+        panic_with_error!(e, TokenError::EpochMintLimitExceeded);
+    }
+    e.storage().instance().set(&key, &(epoch, minted + amount));
+}
+
+/// Amount minted so far in the current epoch.
+pub fn read_epoch_minted(e: &Env, epoch_ledgers: u32) -> i128 {
+    let key = DataKey::EpochMint;
+    let epoch = e.ledger().sequence() / epoch_ledgers;
+    match e.storage().instance().get::<DataKey, (u32, i128)>(&key) {
+        Some((minted_epoch, minted)) if minted_epoch == epoch => minted,
+        _ => 0,
+    }
+}
+
+/// Count a transfer against the current ledger, panicking once `max` is reached.
+pub fn record_ledger_transfer(e: &Env, max: u32) {
+    let key = DataKey::LedgerTransfers;
+    let ledger = e.ledger().sequence();
+    let count = match e.storage().temporary().get::<DataKey, (u32, u32)>(&key) {
+        Some((counted_ledger, count)) if counted_ledger == ledger => count,
+        _ => 0,
+    };
+
+    if count >= max {
+        panic_with_error!(e, TokenError::ThroughputCapReached);
+    }
+    e.storage().temporary().set(&key, &(ledger, count + 1));
+}
+
+pub fn is_wallet_limit_exempt(e: &Env, addr: &Address) -> bool {
+    e.storage().persistent().has(&DataKey::WalletLimitExempt(addr.clone()))
+}
+
+pub fn write_wallet_limit_exempt(e: &Env, addr: &Address, exempt: bool) {
+    let key = DataKey::WalletLimitExempt(addr.clone());
+    if exempt {
+        e.storage().persistent().set(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+/// Panic if receiving `amount` would take a non-exempt `to` past the wallet cap.
+pub fn check_wallet_limit(e: &Env, state: &TokenState, to: &Address, amount: i128) {
+    if let Some(max) = state.max_wallet_balance {
+        if read_balance(e, to.clone()) + amount > max && !is_wallet_limit_exempt(e, to) {
+            panic_with_error!(e, TokenError::ExceedsWalletLimit);
+        }
+    }
+}
+
+pub fn is_cooldown_exempt(e: &Env, addr: &Address) -> bool {
+    e.storage().persistent().has(&DataKey::CooldownExempt(addr.clone()))
+}
+
+pub fn write_cooldown_exempt(e: &Env, addr: &Address, exempt: bool) {
+    let key = DataKey::CooldownExempt(addr.clone());
+    if exempt {
+        e.storage().persistent().set(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+/// Panic if a non-exempt `from` transferred within the last `transfer_cooldown_ledgers`.
+pub fn check_transfer_cooldown(e: &Env, state: &TokenState, from: &Address) {
+    if state.transfer_cooldown_ledgers == 0 {
+        return;
+    }
+    let key = DataKey::LastTransfer(from.clone());
+    if let Some(last) = e.storage().persistent().get::<DataKey, u32>(&key) {
+        let ready_at = last.saturating_add(state.transfer_cooldown_ledgers);
+        if e.ledger().sequence() < ready_at && !is_cooldown_exempt(e, from) {
+            panic_with_error!(e, GuardError::TransferCooldown);
+        }
+    }
+}
+
+/// Record the ledger of a transfer by `from` while a transfer cooldown is configured.
+pub fn record_transfer_ledger(e: &Env, state: &TokenState, from: &Address) {
+    if state.transfer_cooldown_ledgers == 0 {
+        return;
+    }
+    let key = DataKey::LastTransfer(from.clone());
+    e.storage().persistent().set(&key, &e.ledger().sequence());
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
\ No newline at end of file
@@ -1,48 +1,50 @@
+use crate::error::TokenError;
 use crate::storage_types::{DataKey, TokenMetadata, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
-use soroban_sdk::{Env, String};
+use soroban_sdk::{panic_with_error, Env, String};
+
+const MAX_NAME_LEN: u32 = 64;
+const MAX_SYMBOL_LEN: u32 = 12;
+
+pub fn read_metadata(e: &Env) -> Option<TokenMetadata> {
+    let key = DataKey::Metadata;
+    let metadata = e.storage().instance().get::<DataKey, TokenMetadata>(&key)?;
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    Some(metadata)
+}
 
-// Metadata functions
 pub fn read_decimal(e: &Env) -> u32 {
-    let key = DataKey::State;
-    let metadata_key = DataKey::State; // Use separate key for metadata
-    if let Some(metadata) = e.storage().instance().get::<DataKey, TokenMetadata>(&metadata_key) {
-        e.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        metadata.decimal
-    } else {
-        0
-    }
+    read_metadata(e).map_or(0, |metadata| metadata.decimal)
 }
 
 pub fn read_name(e: &Env) -> String {
-    let key = DataKey::State;
-    if let Some(metadata) = e.storage().instance().get::<DataKey, TokenMetadata>(&key) {
-        e.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        metadata.name
-    } else {
-        String::from_str(e, "")
-    }
+    read_metadata(e).map_or(String::from_str(e, ""), |metadata| metadata.name)
 }
 
 pub fn read_symbol(e: &Env) -> String {
-    let key = DataKey::State;
-    if let Some(metadata) = e.storage().instance().get::<DataKey, TokenMetadata>(&key) {
-        e.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        metadata.symbol
-    } else {
-        String::from_str(e, "")
-    }
+    read_metadata(e).map_or(String::from_str(e, ""), |metadata| metadata.symbol)
 }
 
 pub fn write_metadata(e: &Env, metadata: TokenMetadata) {
-    let key = DataKey::State;
+    let key = DataKey::Metadata;
     e.storage().instance().set(&key, &metadata);
     e.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+pub fn validate_metadata(e: &Env, name: &String, symbol: &String) {
+    if name.is_empty() {
+        panic_with_error!(e, TokenError::InvalidMetadata);
+    }
+    if name.len() > MAX_NAME_LEN {
+        panic_with_error!(e, TokenError::InvalidMetadata);
+    }
+    if symbol.is_empty() {
+        panic_with_error!(e, TokenError::InvalidMetadata);
+    }
+    if symbol.len() > MAX_SYMBOL_LEN {
+        panic_with_error!(e, TokenError::InvalidMetadata);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{Address, Env};
+
+pub fn read_nonce(e: &Env, addr: Address) -> u64 {
+    let key = DataKey::Nonce(addr);
+    if let Some(nonce) = e.storage().persistent().get::<DataKey, u64>(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        nonce
+    } else {
+        0
+    }
+}
+
+pub fn increment_nonce(e: &Env, addr: Address) -> u64 {
+    let nonce = read_nonce(e, addr.clone()) + 1;
+    let key = DataKey::Nonce(addr);
+    e.storage().persistent().set(&key, &nonce);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    nonce
+}
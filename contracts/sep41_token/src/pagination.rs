@@ -0,0 +1,11 @@
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val, Vec};
+
+/// The `limit` items of `items` starting at index `start`; empty past the end.
+pub fn page<T>(items: Vec<T>, start: u32, limit: u32) -> Vec<T>
+where
+    T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let start = start.min(items.len());
+    let end = start.saturating_add(limit).min(items.len());
+    items.slice(start..end)
+}
\ No newline at end of file
@@ -0,0 +1,29 @@
+use crate::storage_types::{
+    DataKey, PendingTransfer, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::Env;
+
+pub fn next_reversal_id(e: &Env) -> u64 {
+    let key = DataKey::ReversalCount;
+    let id: u64 = e.storage().instance().get(&key).unwrap_or(0);
+    e.storage().instance().set(&key, &(id + 1));
+    id
+}
+
+pub fn read_pending_transfer(e: &Env, id: u64) -> Option<PendingTransfer> {
+    let key = DataKey::Reversal(id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_pending_transfer(e: &Env, id: u64, pending: &PendingTransfer) {
+    let key = DataKey::Reversal(id);
+    e.storage().persistent().set(&key, pending);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_pending_transfer(e: &Env, id: u64) {
+    let key = DataKey::Reversal(id);
+    e.storage().persistent().remove(&key);
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+use crate::error::TokenError;
+use crate::storage_types::{DataKey, Rule, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{panic_with_error, vec, Address, Env, Vec};
+
+pub fn read_rules(e: &Env) -> Vec<Rule> {
+    let key = DataKey::Rules;
+    e.storage().instance().get(&key).unwrap_or(vec![e])
+}
+
+pub fn write_rules(e: &Env, rules: &Vec<Rule>) {
+    let key = DataKey::Rules;
+    e.storage().instance().set(&key, rules);
+}
+
+/// Evaluate `rules` in order, panicking on the first one the transfer fails.
+pub fn check_rules(e: &Env, rules: &Vec<Rule>, from: &Address, to: &Address, amount: i128) {
+    for rule in rules.iter() {
+        match rule {
+            Rule::MinAmount(min) => {
+                if amount < min {
+                    panic_with_error!(e, TokenError::RuleMinAmount);
+                }
+            }
+            Rule::Allowlist(allowed) => {
+                if !allowed.contains(to) {
+                    panic_with_error!(e, TokenError::RuleAllowlist);
+                }
+            }
+            Rule::Cooldown(ledgers) => {
+                let key = DataKey::LastSent(from.clone());
+                if let Some(last) = e.storage().persistent().get::<DataKey, u32>(&key) {
+                    if e.ledger().sequence() < last.saturating_add(ledgers) {
+                        panic_with_error!(e, TokenError::RuleCooldown);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Record a transfer by `from` for any cooldown rule in `rules`.
+pub fn record_rules_transfer(e: &Env, rules: &Vec<Rule>, from: &Address) {
+    if !rules.iter().any(|rule| matches!(rule, Rule::Cooldown(_))) {
+        return;
+    }
+    let key = DataKey::LastSent(from.clone());
+    e.storage().persistent().set(&key, &e.ledger().sequence());
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use crate::storage_types::{
+    DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{vec, Address, Env, Vec};
+
+pub fn read_snapshot_id(e: &Env) -> u64 {
+    let key = DataKey::SnapshotCount;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn create_snapshot(e: &Env) -> u64 {
+    let id = read_snapshot_id(e) + 1;
+    e.storage().instance().set(&DataKey::SnapshotCount, &id);
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+    let key = DataKey::Snapshot(id);
+    e.storage().persistent().set(&key, &e.ledger().sequence());
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    id
+}
+
+pub fn read_snapshot_ledger(e: &Env, id: u64) -> Option<u32> {
+    let key = DataKey::Snapshot(id);
+    e.storage().persistent().get(&key)
+}
+
+fn read_checkpoints(e: &Env, addr: Address) -> Vec<(u64, i128)> {
+    let key = DataKey::Checkpoints(addr);
+    e.storage().persistent().get(&key).unwrap_or(vec![e])
+}
+
+/// Record `balance` as `addr`'s balance for the latest snapshot, if this is the
+/// first change to it since that snapshot was taken.
+pub fn record_checkpoint(e: &Env, addr: Address, balance: i128) {
+    let snapshot_id = read_snapshot_id(e);
+    if snapshot_id == 0 {
+        return;
+    }
+
+    let mut checkpoints = read_checkpoints(e, addr.clone());
+    if let Some((last_id, _)) = checkpoints.last() {
+        if last_id >= snapshot_id {
+            return;
+        }
+    }
+    checkpoints.push_back((snapshot_id, balance));
+
+    let key = DataKey::Checkpoints(addr);
+    e.storage().persistent().set(&key, &checkpoints);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// The balance as of `snapshot_id` is the first checkpoint taken at or after
+/// it; with no such checkpoint the balance hasn't changed since.
+pub fn read_balance_at(e: &Env, addr: Address, snapshot_id: u64) -> Option<i128> {
+    read_checkpoints(e, addr)
+        .iter()
+        .find(|(id, _)| *id >= snapshot_id)
+        .map(|(_, balance)| balance)
+}
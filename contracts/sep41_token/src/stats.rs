@@ -0,0 +1,58 @@
+#[cfg(feature = "supply-invariant")]
+use crate::error::GuardError;
+use crate::storage_types::{
+    DataKey, TokenState, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+#[cfg(feature = "supply-invariant")]
+use soroban_sdk::panic_with_error;
+use soroban_sdk::{vec, Env, Vec};
+
+/// Number of supply changes kept by `supply_history`; older entries are dropped.
+pub const MAX_SUPPLY_HISTORY: u32 = 100;
+
+pub fn read_max_transfer(e: &Env) -> i128 {
+    let key = DataKey::MaxTransfer;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn record_transfer_amount(e: &Env, amount: i128) {
+    if amount > read_max_transfer(e) {
+        let key = DataKey::MaxTransfer;
+        e.storage().instance().set(&key, &amount);
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+pub fn read_supply_history(e: &Env) -> Vec<(u32, i128, i128)> {
+    let key = DataKey::SupplyHistory;
+    e.storage().persistent().get(&key).unwrap_or(vec![e])
+}
+
+/// Append `(ledger, delta, new_total)` to the supply history, keeping the most recent entries.
+pub fn record_supply_change(e: &Env, delta: i128, new_total: i128) {
+    let key = DataKey::SupplyHistory;
+    let mut history = read_supply_history(e);
+    if history.len() >= MAX_SUPPLY_HISTORY {
+        history.pop_front();
+    }
+    history.push_back((e.ledger().sequence(), delta, new_total));
+    e.storage().persistent().set(&key, &history);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Check that the supply is non-negative and equals everything minted less everything
+/// burned; only built with the `supply-invariant` feature.
+#[cfg(feature = "supply-invariant")]
+pub fn assert_supply_invariant(e: &Env, state: &TokenState) {
+    if state.total_supply < 0 || state.total_minted - state.total_burned != state.total_supply {
+        panic_with_error!(e, GuardError::SupplyInvariantViolated);
+    }
+}
+
+#[cfg(not(feature = "supply-invariant"))]
+#[inline(always)]
+pub fn assert_supply_invariant(_e: &Env, _state: &TokenState) {}
\ No newline at end of file
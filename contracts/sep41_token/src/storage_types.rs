@@ -1,4 +1,6 @@
-use soroban_sdk::{contracttype, Address, String};
+#![allow(clippy::enum_variant_names)]
+
+use soroban_sdk::{contracttype, Address, BytesN, String, Vec};
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
@@ -11,10 +13,49 @@ pub(crate) const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_
 #[contracttype]
 pub enum DataKey {
     Allowance(AllowanceDataKey),
+    AllowanceList(Address),
     Balance(Address),
+    Locked(Address),
+    Frozen(Address),
+    FrozenAccount(u32),
+    FrozenIndex(Address),
+    FrozenCount,
+    Excluded(Address),
+    Allowed(Address),
+    OptedIn(Address),
+    WalletLimitExempt(Address),
+    ExcludedBalance,
     Nonce(Address),
+    Operator(OperatorDataKey),
     Admin,
+    AdminHistory(u32),
+    AdminHistoryLen,
+    PendingAdmin,
+    PendingAdminTimelock,
+    AdminSet,
+    Proposal(BytesN<32>),
+    ExecutingAction,
+    HolderCount,
+    EpochMint,
+    LastReceived(Address),
+    LedgerTransfers,
+    Rules,
+    LastSent(Address),
+    LastTransfer(Address),
+    CooldownExempt(Address),
+    MaxTransfer,
+    SupplyHistory,
     State,
+    Metadata,
+    RollingWindow(Address),
+    Snapshot(u64),
+    SnapshotCount,
+    Checkpoints(Address),
+    Tranche(u64),
+    TrancheCount,
+    Vesting(Address),
+    Reversal(u64),
+    ReversalCount,
 }
 
 #[derive(Clone)]
@@ -24,6 +65,13 @@ pub struct AllowanceDataKey {
     pub spender: Address,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct OperatorDataKey {
+    pub owner: Address,
+    pub operator: Address,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct AllowanceValue {
@@ -31,6 +79,81 @@ pub struct AllowanceValue {
     pub expiration_ledger: u32,
 }
 
+/// A transfer validation rule, evaluated in order by `set_rules`.
+#[derive(Clone)]
+#[contracttype]
+pub enum Rule {
+    /// Transfers must move at least this amount
+    MinAmount(i128),
+    /// Recipients must be one of these addresses
+    Allowlist(Vec<Address>),
+    /// Senders must wait this many ledgers between transfers
+    Cooldown(u32),
+}
+
+/// Supply minted to `recipient` as part of `initialize`.
+#[derive(Clone)]
+#[contracttype]
+pub struct InitialSupply {
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// Supply figures returned together by `economics`.
+#[derive(Clone)]
+#[contracttype]
+pub struct Economics {
+    pub total_supply: i128,
+    /// Total supply less excluded accounts and the contract's own reserve
+    pub circulating_supply: i128,
+    pub max_supply: Option<i128>,
+    pub total_minted: i128,
+    pub total_burned: i128,
+    pub holder_count: u64,
+    /// Supply minted so far this mint epoch, in basis points of the current supply less
+    /// those mints; 0 if that leaves nothing to measure against
+    pub inflation_bps: i128,
+}
+
+/// How a transfer fee that is not a whole number of units is rounded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum FeeRounding {
+    /// Round the fee down, in favour of the recipient
+    Down,
+    /// Round the fee up, so no non-zero transfer escapes the fee
+    Up,
+}
+
+/// Flags and caps returned together by `config`.
+#[derive(Clone)]
+#[contracttype]
+pub struct TokenConfig {
+    pub is_mintable: bool,
+    pub is_burnable: bool,
+    pub is_freezable: bool,
+    pub is_frozen: bool,
+    pub max_supply: Option<i128>,
+    pub fee_bps: u32,
+    pub fee_collector: Option<Address>,
+    pub fee_rounding: FeeRounding,
+    pub rolling_cap: Option<i128>,
+    pub mint_limit_per_epoch: Option<i128>,
+    pub max_transfers_per_ledger: Option<u32>,
+    pub max_transfer_amount: Option<i128>,
+    pub min_transfer_amount: Option<i128>,
+    pub max_wallet_balance: Option<i128>,
+    pub reject_contract_recipient: bool,
+    pub reject_mint_to_contract: bool,
+    pub reject_zero_transfers: bool,
+    pub hooks_enabled: bool,
+    pub allowlist_enabled: bool,
+    pub require_opt_in: bool,
+    pub force_transfer_enabled: bool,
+    pub transfer_cooldown_ledgers: u32,
+    pub burn_address: Option<Address>,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct TokenMetadata {
@@ -44,15 +167,121 @@ pub struct TokenMetadata {
 pub struct TokenState {
     pub admin: Address,
     pub total_supply: i128,
+    pub total_minted: i128,
+    pub total_burned: i128,
     pub max_supply: Option<i128>,
     pub is_mintable: bool,
     pub is_burnable: bool,
     pub is_freezable: bool,
     pub is_frozen: bool,
+    pub rolling_cap: Option<i128>,
+    pub rolling_window_ledgers: u32,
+    pub guardian_contract: Option<Address>,
+    pub fee_bps: u32,
+    pub fee_collector: Option<Address>,
+    pub fee_rounding: FeeRounding,
+    pub min_burn_holding_period: u32,
+    pub reject_contract_recipient: bool,
+    pub reject_mint_to_contract: bool,
+    pub mint_limit_per_epoch: Option<i128>,
+    pub epoch_ledgers: u32,
+    pub max_transfers_per_ledger: Option<u32>,
+    pub reversal_window_ledgers: u32,
+    pub arbiter: Option<Address>,
+    pub hooks_enabled: bool,
+    pub allowlist_enabled: bool,
+    pub require_opt_in: bool,
+    pub reject_zero_transfers: bool,
+    pub max_transfer_amount: Option<i128>,
+    pub min_transfer_amount: Option<i128>,
+    pub max_wallet_balance: Option<i128>,
+    pub force_transfer_enabled: bool,
+    pub transfer_cooldown_ledgers: u32,
+    pub underlying_asset: Option<Address>,
+    pub admin_timelock_ledgers: u32,
+    /// Burns move tokens here instead of reducing supply, while set
+    pub burn_address: Option<Address>,
+    /// Tokens burned to `burn_address` so far
+    pub burned_supply: i128,
+}
+
+/// An admin handoff started by `propose_admin`, acceptable from `ready_at`.
+#[derive(Clone)]
+#[contracttype]
+pub struct PendingAdmin {
+    pub admin: Address,
+    pub ready_at: u32,
 }
 
+/// A lowered admin timelock set by `set_admin_timelock`, in force from `ready_at`.
 #[derive(Clone)]
 #[contracttype]
+pub struct PendingTimelock {
+    pub ledgers: u32,
+    pub ready_at: u32,
+}
+
+/// An N-of-M admin set; while configured, admin calls need `threshold` approvals.
+#[derive(Clone)]
+#[contracttype]
+pub struct AdminSet {
+    pub admins: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// An admin operation that can be proposed and approved by an admin set. Other admin
+/// entrypoints have no action and stay unavailable until the set is dissolved.
+#[derive(Clone)]
+#[contracttype]
+pub enum AdminAction {
+    Mint(Address, i128),
+    SetAdmin(Address),
+    Freeze(Address, String),
+    Unfreeze(Address),
+    SetFrozen(bool),
+    /// Replace the admin set; an empty set returns control to the single admin
+    SetAdmins(Vec<Address>, u32),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Proposal {
+    pub action: AdminAction,
+    pub approvals: Vec<Address>,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct VestingSchedule {
+    pub total: i128,
+    pub start_ledger: u32,
+    pub duration_ledgers: u32,
+    pub claimed: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PendingTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub finalizes_at: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct TrancheSchedule {
+    pub beneficiary: Address,
+    pub total: i128,
+    pub tranches: u32,
+    pub interval_ledgers: u32,
+    pub start_ledger: u32,
+    pub claimed: i128,
+}
+
+/// Why transfers involving an account are blocked, if they are; returned by `frozen_status`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
 pub enum FrozenStatus {
     NotFrozen,
     GloballyFrozen,
@@ -3,6 +3,20 @@
 use super::*;
 use soroban_sdk::{testutils::Address as _, Address, Env, String};
 
+fn initialize(client: &TokenContractClient, env: &Env, admin: &Address) {
+    client.initialize(
+        admin,
+        &7u32,
+        &String::from_str(env, "Test Token"),
+        &String::from_str(env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+}
+
 #[test]
 fn test_token_basic_functionality() {
     let env = Env::default();
@@ -17,12 +31,7 @@ fn test_token_basic_functionality() {
     env.mock_all_auths();
 
     // Initialize token
-    client.initialize(
-        &admin,
-        &7u32,
-        &String::from_str(&env, "Test Token"),
-        &String::from_str(&env, "TEST"),
-    );
+    initialize(&client, &env, &admin);
 
     // Test metadata
     assert_eq!(client.name(), String::from_str(&env, "Test Token"));
@@ -35,7 +44,7 @@ fn test_token_basic_functionality() {
     assert_eq!(client.balance(&user2), 0i128);
 
     // Test minting
-    client.mint(&user1, &1000i128);
+    assert_eq!(client.mint(&user1, &1000i128), 1000i128);
     assert_eq!(client.balance(&user1), 1000i128);
     assert_eq!(client.total_supply(), 1000i128);
 
@@ -47,7 +56,36 @@ fn test_token_basic_functionality() {
 }
 
 #[test]
-#[should_panic(expected = "insufficient balance")]
+fn test_approve_and_transfer_from_flow() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, TokenContract);
+    let client = TokenContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    initialize(&client, &env, &admin);
+    client.mint(&owner, &1000i128);
+
+    // Approve, spend part of it, then revoke the rest
+    client.approve(&owner, &spender, &400i128, &100u32);
+    assert_eq!(client.allowance(&owner, &spender), 400i128);
+
+    client.transfer_from(&spender, &owner, &recipient, &150i128);
+    assert_eq!(client.allowance(&owner, &spender), 250i128);
+    assert_eq!(client.balance(&owner), 850i128);
+    assert_eq!(client.balance(&recipient), 150i128);
+
+    client.approve(&owner, &spender, &0i128, &0u32);
+    assert_eq!(client.allowance(&owner, &spender), 0i128);
+    assert!(client.try_transfer_from(&spender, &owner, &recipient, &1i128).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")] // InsufficientBalance
 fn test_insufficient_balance_transfer() {
     let env = Env::default();
     let contract_id = env.register_contract(None, TokenContract);
@@ -59,12 +97,7 @@ fn test_insufficient_balance_transfer() {
 
     env.mock_all_auths();
 
-    client.initialize(
-        &admin,
-        &7u32,
-        &String::from_str(&env, "Test Token"),
-        &String::from_str(&env, "TEST"),
-    );
+    initialize(&client, &env, &admin);
 
     // Try to transfer more than balance (should panic)
     client.transfer(&user1, &user2, &100i128);
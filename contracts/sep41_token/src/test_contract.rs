@@ -0,0 +1,4408 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::allowance::MAX_TRACKED_SPENDERS;
+use crate::contract::{TokenContract, TokenContractClient};
+use crate::error::{GuardError, TokenError};
+use crate::metadata::{read_decimal, read_name, read_symbol};
+use crate::stats::MAX_SUPPLY_HISTORY;
+use crate::storage_types::{AdminAction, AllowanceDataKey, DataKey, FeeRounding, FrozenStatus, InitialSupply, Rule, BALANCE_BUMP_AMOUNT, DAY_IN_LEDGERS};
+use crate::test_events::{assert_events_since, event_mark};
+use soroban_sdk::{
+    testutils::{storage::Persistent as _, Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+    token, vec,
+    xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, String,
+};
+
+mod guardian {
+    use soroban_sdk::{contract, contractimpl, symbol_short, vec, Address, Env, Vec};
+
+    #[contract]
+    pub struct MockGuardian;
+
+    #[contractimpl]
+    impl MockGuardian {
+        pub fn on_freeze(env: Env, addr: Address) {
+            let mut notified = Self::notified(env.clone());
+            notified.push_back(addr);
+            env.storage().instance().set(&symbol_short!("notified"), &notified);
+        }
+
+        pub fn notified(env: Env) -> Vec<Address> {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("notified"))
+                .unwrap_or(vec![&env])
+        }
+    }
+}
+
+mod failing_guardian {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct FailingGuardian;
+
+    #[contractimpl]
+    impl FailingGuardian {
+        pub fn on_freeze(_env: Env, _addr: Address) {
+            panic!("guardian unavailable");
+        }
+    }
+}
+
+mod receiver {
+    use soroban_sdk::{contract, contractimpl, symbol_short, vec, Address, Env, Vec};
+
+    #[contract]
+    pub struct MockReceiver;
+
+    #[contractimpl]
+    impl MockReceiver {
+        pub fn on_token_received(env: Env, from: Address, amount: i128) {
+            let mut received = Self::received(env.clone());
+            received.push_back((from, amount));
+            env.storage().instance().set(&symbol_short!("received"), &received);
+        }
+
+        pub fn received(env: Env) -> Vec<(Address, i128)> {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("received"))
+                .unwrap_or(vec![&env])
+        }
+    }
+}
+
+mod reentrant_receiver {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    use crate::contract::TokenContractClient;
+
+    /// Tries to send the tokens it receives straight back through `transfer`.
+    #[contract]
+    pub struct ReentrantReceiver;
+
+    #[contractimpl]
+    impl ReentrantReceiver {
+        pub fn set_token(env: Env, token: Address) {
+            env.storage().instance().set(&symbol_short!("token"), &token);
+        }
+
+        pub fn on_token_received(env: Env, from: Address, amount: i128) {
+            let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+            let token = TokenContractClient::new(&env, &token);
+            let blocked = token.try_transfer(&env.current_contract_address(), &from, &amount).is_err();
+            env.storage().instance().set(&symbol_short!("blocked"), &blocked);
+        }
+
+        pub fn blocked(env: Env) -> bool {
+            env.storage().instance().get(&symbol_short!("blocked")).unwrap_or(false)
+        }
+    }
+}
+
+use failing_guardian::FailingGuardian;
+use guardian::{MockGuardian, MockGuardianClient};
+use receiver::{MockReceiver, MockReceiverClient};
+use reentrant_receiver::{ReentrantReceiver, ReentrantReceiverClient};
+
+fn register_token<'a>(env: &Env) -> TokenContractClient<'a> {
+    let contract_id = env.register_contract(None, TokenContract);
+    TokenContractClient::new(env, &contract_id)
+}
+
+fn create_token<'a>(env: &Env, admin: &Address) -> TokenContractClient<'a> {
+    let client = register_token(env);
+    client.initialize(
+        admin,
+        &7u32,
+        &String::from_str(env, "Test Token"),
+        &String::from_str(env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+    client
+}
+
+fn advance_ledger(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| li.sequence_number += ledgers);
+}
+
+/// Authorize the next call as `owner` having signed a permit for exactly these arguments.
+fn authorize_permit(
+    env: &Env,
+    client: &TokenContractClient,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+    nonce: u64,
+) {
+    env.mock_auths(&[MockAuth {
+        address: owner,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "permit",
+            args: (spender.clone(), amount, expiration_ledger, nonce).into_val(env),
+            sub_invokes: &[],
+        },
+    }]);
+}
+
+#[test]
+fn test_mint_and_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user2, &300i128);
+
+    assert_eq!(client.balance(&user1), 700i128);
+    assert_eq!(client.balance(&user2), 300i128);
+    assert_eq!(client.total_supply(), 1000i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")] // InsufficientBalance
+fn test_transfer_insufficient_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &100i128);
+    client.transfer(&user1, &user2, &101i128);
+}
+
+#[test]
+fn test_admin_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.admin_burn(&user1, &400i128);
+
+    assert_eq!(client.balance(&user1), 600i128);
+    assert_eq!(client.total_supply(), 600i128);
+    assert_eq!(client.economics().total_burned, 400i128);
+}
+
+#[test]
+fn test_approve_and_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &500i128, &200u32);
+    assert_eq!(client.allowance(&user1, &spender), 500i128);
+
+    client.transfer_from(&spender, &user1, &user2, &200i128);
+    assert_eq!(client.allowance(&user1, &spender), 300i128);
+    assert_eq!(client.balance(&user1), 800i128);
+    assert_eq!(client.balance(&user2), 200i128);
+}
+
+#[test]
+fn test_infinite_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let finite = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &i128::MAX, &1000u32);
+    client.approve(&user1, &finite, &500i128, &1000u32);
+
+    client.transfer_from(&spender, &user1, &user2, &300i128);
+    client.transfer_from(&spender, &user1, &user2, &300i128);
+    assert_eq!(client.allowance(&user1, &spender), i128::MAX);
+
+    client.transfer_from(&finite, &user1, &user2, &300i128);
+    assert_eq!(client.allowance(&user1, &finite), 200i128);
+    assert_eq!(client.balance(&user2), 900i128);
+
+    // Even an infinite allowance ends at its expiration
+    advance_ledger(&env, 1001);
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &100i128),
+        Err(Ok(TokenError::InsufficientAllowance.into()))
+    );
+}
+
+#[test]
+fn test_spent_allowance_is_removed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: user1.clone(),
+        spender: spender.clone(),
+    });
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &300i128, &1000u32);
+    client.transfer_from(&spender, &user1, &user2, &100i128);
+    assert!(env.as_contract(&client.address, || env.storage().temporary().has(&key)));
+
+    client.transfer_from(&spender, &user1, &user2, &200i128);
+    assert!(!env.as_contract(&client.address, || env.storage().temporary().has(&key)));
+    assert_eq!(client.allowance(&user1, &spender), 0i128);
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &1i128),
+        Err(Ok(TokenError::InsufficientAllowance.into()))
+    );
+}
+
+#[test]
+fn test_revoke_all_allowances() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spenders = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    for spender in spenders.iter() {
+        client.approve(&user1, spender, &100i128, &1000u32);
+    }
+    // Fully spent or re-approved allowances are only counted once, if at all
+    client.transfer_from(&spenders[0], &user1, &user2, &100i128);
+    client.approve(&user1, &spenders[1], &200i128, &1000u32);
+
+    assert_eq!(client.revoke_all_allowances(&user1), 2u32);
+    for spender in spenders.iter() {
+        assert_eq!(client.allowance(&user1, spender), 0i128);
+    }
+    assert_eq!(client.revoke_all_allowances(&user1), 0u32);
+    assert!(client.try_transfer_from(&spenders[2], &user1, &user2, &1i128).is_err());
+}
+
+#[test]
+fn test_allowance_spender_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let mut spenders = std::vec::Vec::new();
+    for _ in 0..MAX_TRACKED_SPENDERS {
+        let spender = Address::generate(&env);
+        client.approve(&user, &spender, &100i128, &1000u32);
+        spenders.push(spender);
+    }
+
+    // A full list still accepts changes to existing allowances, but no new spender
+    client.approve(&user, &spenders[0], &200i128, &1000u32);
+    let extra = Address::generate(&env);
+    assert_eq!(
+        client.try_approve(&user, &extra, &100i128, &1000u32),
+        Err(Ok(GuardError::TooManySpenders.into()))
+    );
+
+    // Revoking one frees its slot
+    client.approve(&user, &spenders[1], &0i128, &0u32);
+    client.approve(&user, &extra, &100i128, &1000u32);
+    assert_eq!(client.revoke_all_allowances(&user), MAX_TRACKED_SPENDERS);
+}
+
+#[test]
+fn test_approve_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    advance_ledger(&env, 100);
+
+    // The current ledger is still a live expiration
+    client.approve(&user1, &spender, &500i128, &100u32);
+    client.approve(&user1, &spender, &500i128, &200u32);
+    assert_eq!(client.allowance(&user1, &spender), 500i128);
+
+    // Revoking accepts any expiration
+    client.approve(&user1, &spender, &0i128, &0u32);
+    assert_eq!(client.allowance(&user1, &spender), 0i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #48)")] // InvalidExpiration
+fn test_approve_past_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    advance_ledger(&env, 100);
+
+    client.approve(&user1, &spender, &500i128, &99u32);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")] // InsufficientAllowance
+fn test_transfer_from_insufficient_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &100i128, &200u32);
+    client.transfer_from(&spender, &user1, &user2, &101i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")] // Frozen
+fn test_transfer_from_frozen_account() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.freeze(&user1, &String::from_str(&env, "compliance"));
+    client.transfer(&user1, &user2, &100i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")] // GloballyFrozen
+fn test_transfer_while_globally_frozen() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_frozen(&true);
+    client.transfer(&user1, &user2, &100i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")] // ExceedsMaxSupply
+fn test_mint_exceeds_max_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = register_token(&env);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &Some(1000i128),
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+
+    // Minting up to the cap is fine
+    client.mint(&user1, &1000i128);
+    client.mint(&user1, &1i128);
+}
+
+#[test]
+fn test_mint_up_to() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = register_token(&env);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &Some(1000i128),
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+
+    // Fits fully
+    assert_eq!(client.mint_up_to(&user1, &600i128), 600i128);
+    // Fits partially
+    assert_eq!(client.mint_up_to(&user1, &600i128), 400i128);
+    assert_eq!(client.total_supply(), 1000i128);
+    // Does not fit at all
+    assert_eq!(client.mint_up_to(&user1, &1i128), 0i128);
+    assert_eq!(client.balance(&user1), 1000i128);
+
+    // The strict mint still panics at the cap
+    assert_eq!(
+        client.try_mint(&user1, &1i128),
+        Err(Ok(TokenError::ExceedsMaxSupply.into()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")] // AlreadyInitialized
+fn test_initialize_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+}
+
+fn balance_ttl(env: &Env, client: &TokenContractClient, addr: &Address) -> u32 {
+    env.as_contract(&client.address, || {
+        env.storage().persistent().get_ttl(&DataKey::Balance(addr.clone()))
+    })
+}
+
+#[test]
+fn test_balances_of() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.balances_of(&vec![&env]), vec![&env]);
+
+    let mut ids = vec![&env];
+    for i in 0..5 {
+        let user = Address::generate(&env);
+        client.mint(&user, &(100 * (i + 1)));
+        ids.push_back(user);
+    }
+    // Accounts without a balance and repeated accounts are reported too
+    ids.push_back(Address::generate(&env));
+    ids.push_back(ids.get(0).unwrap());
+
+    let balances = client.balances_of(&ids);
+    assert_eq!(balances.len(), ids.len());
+    for (id, balance) in ids.iter().zip(balances.iter()) {
+        assert_eq!(balance, client.balance(&id));
+    }
+    assert_eq!(balances.get(5).unwrap(), 0i128);
+}
+
+#[test]
+fn test_balance_view() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    assert_eq!(client.balance_view(&user1), client.balance(&user1));
+    assert_eq!(client.balance_view(&user2), 0i128);
+}
+
+#[test]
+fn test_balance_read_extends_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    assert_eq!(balance_ttl(&env, &client, &user1), BALANCE_BUMP_AMOUNT);
+
+    // Once the entry drops below the threshold, reading the balance bumps it again
+    advance_ledger(&env, 2 * DAY_IN_LEDGERS);
+    assert_eq!(balance_ttl(&env, &client, &user1), BALANCE_BUMP_AMOUNT - 2 * DAY_IN_LEDGERS);
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert_eq!(balance_ttl(&env, &client, &user1), BALANCE_BUMP_AMOUNT);
+}
+
+#[test]
+fn test_balance_writes_extend_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.mint(&user2, &1000i128);
+    advance_ledger(&env, 2 * DAY_IN_LEDGERS);
+
+    // Both the spent and the received balance entries are bumped
+    client.transfer(&user1, &user2, &100i128);
+    assert_eq!(balance_ttl(&env, &client, &user1), BALANCE_BUMP_AMOUNT);
+    assert_eq!(balance_ttl(&env, &client, &user2), BALANCE_BUMP_AMOUNT);
+
+    advance_ledger(&env, 2 * DAY_IN_LEDGERS);
+    client.burn_self(&user2, &100i128);
+    assert_eq!(balance_ttl(&env, &client, &user2), BALANCE_BUMP_AMOUNT);
+}
+
+#[test]
+fn test_transfer_tranched() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+
+    // 1000 over 3 tranches: 333, 333, then 334 with the rounding dust
+    let id = client.transfer_tranched(&user1, &user2, &1000i128, &3u32, &100u32);
+    assert_eq!(client.balance(&user1), 0i128);
+    assert_eq!(client.balance(&user2), 0i128);
+    assert_eq!(client.balance(&client.address), 1000i128);
+
+    // Nothing is released before the first interval
+    advance_ledger(&env, 99);
+    assert_eq!(client.claim_tranches(&id), 0i128);
+
+    advance_ledger(&env, 1);
+    assert_eq!(client.claim_tranches(&id), 333i128);
+    assert_eq!(client.balance(&user2), 333i128);
+
+    // Claiming twice in the same interval releases nothing new
+    assert_eq!(client.claim_tranches(&id), 0i128);
+
+    advance_ledger(&env, 100);
+    assert_eq!(client.claim_tranches(&id), 333i128);
+    assert_eq!(client.balance(&user2), 666i128);
+
+    advance_ledger(&env, 250);
+    assert_eq!(client.claim_tranches(&id), 334i128);
+    assert_eq!(client.balance(&user2), 1000i128);
+    assert_eq!(client.balance(&client.address), 0i128);
+    assert!(client.tranche_schedule(&id).is_none());
+    assert_eq!(client.total_supply(), 1000i128);
+}
+
+#[test]
+fn test_transfer_tranched_applies_transfer_policy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+
+    // The recipient must have opted in, as for a direct transfer
+    client.set_require_opt_in(&true);
+    assert_eq!(
+        client.try_transfer_tranched(&user1, &user2, &600i128, &2u32, &100u32),
+        Err(Ok(GuardError::NotOptedIn.into()))
+    );
+    client.opt_in(&user2);
+    let id = client.transfer_tranched(&user1, &user2, &600i128, &2u32, &100u32);
+
+    // Claims are checked against the rules in force when they are made
+    client.set_max_wallet_balance(&Some(200i128));
+    advance_ledger(&env, 100);
+    assert_eq!(
+        client.try_claim_tranches(&id),
+        Err(Ok(TokenError::ExceedsWalletLimit.into()))
+    );
+    client.set_max_wallet_balance(&None);
+    assert_eq!(client.claim_tranches(&id), 300i128);
+
+    client.set_allowlist_enabled(&true);
+    advance_ledger(&env, 100);
+    assert_eq!(
+        client.try_claim_tranches(&id),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+    client.add_to_allowlist(&user2);
+    assert_eq!(client.claim_tranches(&id), 300i128);
+    assert_eq!(client.balance(&user2), 600i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")] // InvalidAmount
+fn test_transfer_tranched_zero_tranches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.transfer_tranched(&user1, &user2, &1000i128, &0u32, &100u32);
+}
+
+
+#[test]
+fn test_permit() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    env.mock_all_auths();
+    let client = create_token(&env, &admin);
+    client.mint(&owner, &1000i128);
+
+    // The owner's authorization covers only the arguments it was signed for
+    authorize_permit(&env, &client, &owner, &spender, 500, 1000, 0);
+    assert!(client.try_permit(&owner, &spender, &600i128, &1000u32, &0u64).is_err());
+
+    authorize_permit(&env, &client, &owner, &spender, 500, 1000, 0);
+    client.permit(&owner, &spender, &500i128, &1000u32, &0u64);
+    assert_eq!(client.allowance(&owner, &spender), 500i128);
+
+    env.mock_all_auths();
+    client.transfer_from(&spender, &owner, &user2, &200i128);
+    assert_eq!(client.balance(&user2), 200i128);
+    assert_eq!(client.allowance(&owner, &spender), 300i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")] // InvalidNonce
+fn test_permit_replayed_nonce() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    env.mock_all_auths();
+    let client = create_token(&env, &admin);
+
+    authorize_permit(&env, &client, &owner, &spender, 500, 1000, 0);
+    client.permit(&owner, &spender, &500i128, &1000u32, &0u64);
+
+    // Even when re-authorized, a permit must fail once its nonce has been used
+    authorize_permit(&env, &client, &owner, &spender, 500, 1000, 0);
+    client.permit(&owner, &spender, &500i128, &1000u32, &0u64);
+}
+
+#[test]
+fn test_nonce_increments() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    env.mock_all_auths();
+    let client = create_token(&env, &admin);
+    assert_eq!(client.nonce(&owner), 0u64);
+
+    for nonce in 0..3u64 {
+        authorize_permit(&env, &client, &owner, &spender, 100, 1000, nonce);
+        client.permit(&owner, &spender, &100i128, &1000u32, &nonce);
+        assert_eq!(client.nonce(&owner), nonce + 1);
+    }
+
+    // Nonces are tracked per owner
+    assert_eq!(client.nonce(&spender), 0u64);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")] // PermitExpired
+fn test_permit_expired() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    env.mock_all_auths();
+    let client = create_token(&env, &admin);
+
+    advance_ledger(&env, 101);
+    authorize_permit(&env, &client, &owner, &spender, 500, 100, 0);
+    client.permit(&owner, &spender, &500i128, &100u32, &0u64);
+}
+
+#[test]
+fn test_admin_at() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    let client = create_token(&env, &admin);
+
+    advance_ledger(&env, 50);
+    client.set_admin(&new_admin);
+    let changed_at = env.ledger().sequence();
+
+    advance_ledger(&env, 50);
+    assert_eq!(client.admin_at(&100u32), admin);
+    assert_eq!(client.admin_at(&(changed_at - 1)), admin);
+    assert_eq!(client.admin_at(&changed_at), new_admin);
+    assert_eq!(client.admin_at(&env.ledger().sequence()), new_admin);
+
+    // Every change stays queryable, and the last change within a ledger wins
+    let mut admins = std::vec::Vec::new();
+    for _ in 0..5 {
+        advance_ledger(&env, 10);
+        let next = Address::generate(&env);
+        client.set_admin(&next);
+        admins.push((env.ledger().sequence(), next));
+    }
+    let last = Address::generate(&env);
+    client.set_admin(&last);
+    for (ledger, expected) in admins[..4].iter() {
+        assert_eq!(client.admin_at(ledger), *expected);
+        assert_eq!(client.admin_at(&(ledger + 9)), *expected);
+    }
+    assert_eq!(client.admin_at(&admins[4].0), last);
+    assert_eq!(client.admin_at(&changed_at), new_admin);
+    assert_eq!(client.admin_at(&100u32), admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #25)")] // NoAdminAtLedger
+fn test_admin_at_before_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    let client = create_token(&env, &admin);
+
+    client.admin_at(&99u32);
+}
+
+#[test]
+fn test_rolling_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_rolling_cap(&Some(100i128), &10u32);
+
+    client.transfer(&user1, &user2, &60i128);
+    advance_ledger(&env, 5);
+    client.transfer(&user1, &user2, &40i128);
+    assert!(client.try_transfer(&user1, &user2, &1i128).is_err());
+
+    // The first transfer ages out of the window, freeing its 60
+    advance_ledger(&env, 5);
+    assert!(client.try_transfer(&user1, &user2, &61i128).is_err());
+    client.transfer(&user1, &user2, &60i128);
+    assert_eq!(client.balance(&user2), 160i128);
+
+    // Receiving doesn't count against the recipient's window
+    client.transfer(&user2, &user1, &100i128);
+}
+
+#[test]
+fn test_rolling_cap_unbounded_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_rolling_cap(&Some(100i128), &u32::MAX);
+
+    // A window reaching past the last ledger never lets a transfer age out
+    advance_ledger(&env, 10);
+    client.transfer(&user1, &user2, &60i128);
+    advance_ledger(&env, 1_000);
+    client.transfer(&user1, &user2, &40i128);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &1i128),
+        Err(Ok(TokenError::RollingCapExceeded.into()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")] // RollingCapExceeded
+fn test_rolling_cap_exceeded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_rolling_cap(&Some(100i128), &10u32);
+
+    client.transfer(&user1, &user2, &100i128);
+    client.transfer(&user1, &user2, &1i128);
+}
+
+#[test]
+fn test_initialize_max_decimals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+
+    client.initialize(
+        &admin,
+        &18u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+    assert_eq!(client.admin(), admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")] // InvalidDecimals
+fn test_initialize_excessive_decimals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+
+    client.initialize(
+        &admin,
+        &19u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+}
+
+#[test]
+fn test_is_emergency() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    assert!(!client.is_emergency());
+
+    client.set_frozen(&true);
+    assert!(client.is_emergency());
+
+    client.set_frozen(&false);
+    assert!(!client.is_emergency());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")] // InvalidMetadata
+fn test_initialize_empty_name() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, ""),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")] // InvalidMetadata
+fn test_initialize_empty_symbol() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, ""),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")] // InvalidMetadata
+fn test_initialize_long_symbol() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TOOLONGSYMBOL"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+}
+
+#[test]
+fn test_self_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user1, &400i128);
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert_eq!(client.total_supply(), 1000i128);
+
+    let (contract, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(contract, client.address);
+    assert_eq!(topics, ("transfer", user1.clone(), user1.clone()).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 400i128);
+
+    // The balance check still applies
+    assert!(client.try_transfer(&user1, &user1, &1001i128).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")] // Frozen
+fn test_self_transfer_frozen() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.freeze(&user1, &String::from_str(&env, "compliance"));
+    client.transfer(&user1, &user1, &400i128);
+}
+
+#[test]
+fn test_allowance_detail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.allowance_detail(&user1, &spender), (0i128, 0u32));
+
+    client.approve(&user1, &spender, &500i128, &200u32);
+    assert_eq!(client.allowance_detail(&user1, &spender), (500i128, 200u32));
+
+    // The allowance stays live until its expiration ledger
+    advance_ledger(&env, 150);
+    assert_eq!(client.allowance_detail(&user1, &spender), (500i128, 200u32));
+
+    advance_ledger(&env, 51);
+    assert_eq!(client.allowance_detail(&user1, &spender).0, 0i128);
+}
+
+#[test]
+fn test_total_holders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    assert_eq!(client.total_holders(), 0u64);
+
+    client.mint(&user1, &1000i128);
+    assert_eq!(client.total_holders(), 1u64);
+
+    // Topping up an existing holder doesn't add a holder
+    client.mint(&user1, &500i128);
+    assert_eq!(client.total_holders(), 1u64);
+
+    client.transfer(&user1, &user2, &500i128);
+    assert_eq!(client.total_holders(), 2u64);
+
+    // Zeroing a balance removes the holder
+    client.transfer(&user1, &user2, &1000i128);
+    assert_eq!(client.total_holders(), 1u64);
+
+    // Re-funding a previously zeroed account counts it again
+    client.transfer(&user2, &user1, &1i128);
+    assert_eq!(client.total_holders(), 2u64);
+}
+
+#[test]
+fn test_guardian_notified_on_freeze() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let guardian_id = env.register_contract(None, MockGuardian);
+    let guardian = MockGuardianClient::new(&env, &guardian_id);
+    client.set_guardian(&Some(guardian_id.clone()));
+
+    client.freeze(&user1, &String::from_str(&env, "compliance"));
+    client.set_frozen(&true);
+    client.set_frozen(&false);
+    assert_eq!(guardian.notified(), vec![&env, user1.clone(), client.address.clone()]);
+
+    // Without a guardian configured nothing is notified
+    client.set_guardian(&None);
+    client.unfreeze(&user1);
+    client.freeze(&user1, &String::from_str(&env, "compliance"));
+    assert_eq!(guardian.notified().len(), 2);
+}
+
+#[test]
+fn test_failing_guardian_does_not_block_freeze() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let guardian_id = env.register_contract(None, FailingGuardian);
+    client.set_guardian(&Some(guardian_id));
+
+    client.freeze(&user1, &String::from_str(&env, "compliance"));
+    assert!(client.is_frozen(&user1));
+}
+
+#[test]
+fn test_freeze_reason() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.freeze_reason(&user1), None);
+
+    let reason = String::from_str(&env, "court order 1234");
+    client.freeze(&user1, &reason);
+    assert!(client.is_frozen(&user1));
+    assert_eq!(client.freeze_reason(&user1), Some(reason.clone()));
+
+    let (_, _, data) = env.events().all().last().unwrap();
+    let (_, addr, logged): (Address, Address, String) = data.into_val(&env);
+    assert_eq!(addr, user1);
+    assert_eq!(logged, reason);
+
+    // Unfreezing clears the reason along with the flag
+    client.unfreeze(&user1);
+    assert!(!client.is_frozen(&user1));
+    assert_eq!(client.freeze_reason(&user1), None);
+}
+
+#[test]
+fn test_frozen_status() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.frozen_status(&user1), FrozenStatus::NotFrozen);
+
+    client.freeze(&user1, &String::from_str(&env, "compromised key"));
+    assert_eq!(client.frozen_status(&user1), FrozenStatus::AccountFrozen(user1.clone()));
+
+    // The global freeze is reported even for accounts frozen individually
+    client.set_frozen(&true);
+    assert_eq!(client.frozen_status(&user1), FrozenStatus::GloballyFrozen);
+    assert_eq!(client.frozen_status(&admin), FrozenStatus::GloballyFrozen);
+}
+
+#[test]
+fn test_frozen_accounts_page() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let reason = String::from_str(&env, "compliance");
+
+    let mut accounts = vec![&env];
+    for _ in 0..5 {
+        let account = Address::generate(&env);
+        client.freeze(&account, &reason);
+        accounts.push_back(account);
+    }
+    // Refreezing does not duplicate an entry; unfreezing drops it, and the last
+    // frozen account takes its slot
+    client.freeze(&accounts.get(1).unwrap(), &reason);
+    client.unfreeze(&accounts.get(0).unwrap());
+    accounts.set(0, accounts.get(4).unwrap());
+    accounts.pop_back();
+
+    assert_eq!(client.frozen_accounts_page(&0u32, &2u32), accounts.slice(0..2));
+    assert_eq!(client.frozen_accounts_page(&2u32, &2u32), accounts.slice(2..4));
+    assert_eq!(client.frozen_accounts_page(&3u32, &10u32), accounts.slice(3..4));
+    assert_eq!(client.frozen_accounts_page(&4u32, &2u32), vec![&env]);
+    assert_eq!(client.frozen_accounts_page(&u32::MAX, &u32::MAX), vec![&env]);
+
+    let last = accounts.get(3).unwrap();
+    client.unfreeze(&last);
+    client.freeze(&last, &reason);
+    assert_eq!(client.frozen_accounts_page(&0u32, &10u32), accounts);
+}
+
+#[test]
+fn test_account_hash() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let hash = client.account_hash(&user1);
+    assert_eq!(hash, client.account_hash(&user1));
+    assert_ne!(hash, client.account_hash(&user2));
+
+    // Matches what an off-chain indexer computes from the address XDR
+    let expected: BytesN<32> = env.crypto().sha256(&user1.clone().to_xdr(&env)).into();
+    assert_eq!(hash, expected);
+}
+
+#[test]
+fn test_transfer_fee_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &10_000i128);
+    client.set_transfer_fee(&250u32, &Some(treasury.clone()));
+    assert_eq!(client.transfer_fee(), (250u32, Some(treasury.clone())));
+
+    // 2.5% of 1000 is exactly 25; the fee leg is emitted before the transfer itself
+    let mark = event_mark(&env);
+    client.transfer(&user1, &user2, &1000i128);
+    assert_events_since(&env, mark, &["transfer", "transfer"]);
+    assert_eq!(client.balance(&user2), 975i128);
+    assert_eq!(client.balance(&treasury), 25i128);
+
+    // 2.5% of 99 is 2.475, which rounds down to 2
+    client.approve(&user1, &spender, &99i128, &1000u32);
+    client.transfer_from(&spender, &user1, &user2, &99i128);
+    assert_eq!(client.balance(&user2), 975i128 + 97);
+    assert_eq!(client.balance(&treasury), 27i128);
+
+    assert_eq!(client.balance(&user1), 10_000i128 - 1099);
+    assert_eq!(client.total_supply(), 10_000i128);
+}
+
+#[test]
+fn test_fee_rounding() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_transfer_fee(&333u32, &Some(treasury.clone()));
+    assert_eq!(client.config().fee_rounding, FeeRounding::Down);
+
+    // 3.33% of 7 is 0.2331: rounding down lets the whole transfer through fee-free
+    client.transfer(&user1, &user2, &7i128);
+    assert_eq!(client.balance(&user2), 7i128);
+    assert_eq!(client.balance(&treasury), 0i128);
+
+    // Rounding up charges one unit, and recipient plus fee still add up to 7
+    client.set_fee_rounding(&FeeRounding::Up);
+    client.transfer(&user1, &user2, &7i128);
+    assert_eq!(client.balance(&user2), 13i128);
+    assert_eq!(client.balance(&treasury), 1i128);
+
+    // An exact fee is not bumped: 3.33% of 10_000 is 333
+    client.mint(&user1, &10_000i128);
+    client.transfer(&user1, &user2, &10_000i128);
+    assert_eq!(client.balance(&treasury), 334i128);
+    assert_eq!(client.balance(&user2), 13i128 + 9_667);
+
+    assert_eq!(client.balance(&user1) + client.balance(&user2) + client.balance(&treasury), client.total_supply());
+}
+
+#[test]
+fn test_zero_fee_is_plain_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_transfer_fee(&0u32, &Some(treasury.clone()));
+
+    client.transfer(&user1, &user2, &1000i128);
+    assert_eq!(client.balance(&user2), 1000i128);
+    assert_eq!(client.balance(&treasury), 0i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")] // InvalidConfig
+fn test_transfer_fee_without_collector() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_transfer_fee(&100u32, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")] // InvalidConfig
+fn test_transfer_fee_too_high() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_transfer_fee(&10_001u32, &Some(treasury));
+}
+
+#[test]
+fn test_snapshot_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    let first = client.snapshot();
+    assert_eq!(client.snapshot_ledger(&first), env.ledger().sequence());
+
+    client.transfer(&user1, &user2, &300i128);
+    client.transfer(&user1, &user2, &200i128);
+    let second = client.snapshot();
+
+    client.transfer(&user2, &user1, &100i128);
+    client.mint(&user2, &50i128);
+
+    assert_eq!(client.balance_at(&user1, &first), 1000i128);
+    assert_eq!(client.balance_at(&user2, &first), 0i128);
+    assert_eq!(client.balance_at(&user1, &second), 500i128);
+    assert_eq!(client.balance_at(&user2, &second), 500i128);
+
+    // Accounts untouched since a snapshot report their current balance
+    let third = client.snapshot();
+    assert_eq!(client.balance_at(&user1, &third), 600i128);
+    assert_eq!(client.balance_at(&user2, &third), 450i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")] // UnknownSnapshot
+fn test_balance_at_unknown_snapshot() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.snapshot();
+    client.balance_at(&user1, &2u64);
+}
+
+#[test]
+fn test_burn_holding_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_min_burn_holding_period(&100u32);
+    client.mint(&user1, &1000i128);
+    assert!(client.try_admin_burn(&user1, &100i128).is_err());
+
+    advance_ledger(&env, 99);
+    assert!(client.try_admin_burn(&user1, &100i128).is_err());
+
+    advance_ledger(&env, 1);
+    client.admin_burn(&user1, &100i128);
+    assert_eq!(client.balance(&user1), 900i128);
+
+    // Receiving again restarts the holding period
+    client.mint(&user1, &1i128);
+    assert!(client.try_admin_burn(&user1, &100i128).is_err());
+
+    // A period reaching past the last ledger saturates instead of overflowing
+    client.set_min_burn_holding_period(&u32::MAX);
+    assert_eq!(
+        client.try_admin_burn(&user1, &100i128),
+        Err(Ok(TokenError::HoldingPeriodNotElapsed.into()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")] // HoldingPeriodNotElapsed
+fn test_burn_within_holding_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_min_burn_holding_period(&100u32);
+    client.mint(&user1, &1000i128);
+    client.admin_burn(&user1, &100i128);
+}
+
+#[test]
+fn test_burn_self() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.burn_self(&user1, &400i128);
+
+    // Only the holder's authorization was needed
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, user1);
+
+    assert_eq!(client.balance(&user1), 600i128);
+    assert_eq!(client.total_supply(), 600i128);
+
+    client.admin_burn(&user1, &100i128);
+    assert_eq!(client.balance(&user1), 500i128);
+    assert_eq!(client.total_supply(), 500i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #49)")] // InsufficientBalanceToBurn
+fn test_burn_self_non_holder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.burn_self(&user2, &1i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #49)")] // InsufficientBalanceToBurn
+fn test_admin_burn_exceeds_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.admin_burn(&user1, &1001i128);
+}
+
+#[test]
+fn test_burn_address_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let dead = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_burn_address(&Some(dead.clone()));
+    assert_eq!(client.config().burn_address, Some(dead.clone()));
+
+    client.burn_self(&user1, &100i128);
+    client.admin_burn(&user1, &50i128);
+    assert_eq!(client.total_supply(), 1000i128);
+    assert_eq!(client.burned_supply(), 150i128);
+    assert_eq!(client.balance(&dead), 150i128);
+    assert_eq!(client.balance(&user1), 850i128);
+    assert_eq!(client.economics().total_burned, 0i128);
+
+    // Switching back reduces supply again and leaves the accumulated amount alone
+    client.set_burn_address(&None);
+    client.burn_self(&user1, &100i128);
+    assert_eq!(client.total_supply(), 900i128);
+    assert_eq!(client.burned_supply(), 150i128);
+    assert_eq!(client.economics().total_burned, 100i128);
+}
+
+#[test]
+fn test_burn_address_cannot_be_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(
+        client.try_set_burn_address(&Some(client.address.clone())),
+        Err(Ok(TokenError::InvalidConfig.into()))
+    );
+}
+
+#[test]
+fn test_force_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lost = Address::generate(&env);
+    let recovery = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&lost, &1000i128);
+    client.set_force_transfer_enabled(&true);
+    client.force_transfer(&lost, &recovery, &1000i128);
+
+    // Only the admin authorized the move
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.balance(&lost), 0i128);
+    assert_eq!(client.balance(&recovery), 1000i128);
+    assert_eq!(client.total_supply(), 1000i128);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("force_transfer",).into_val(&env));
+    let data: (Address, Address, i128) = data.into_val(&env);
+    assert_eq!(data, (lost, recovery, 1000i128));
+}
+
+#[test]
+fn test_force_transfer_disabled() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lost = Address::generate(&env);
+    let recovery = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&lost, &1000i128);
+    assert_eq!(
+        client.try_force_transfer(&lost, &recovery, &1000i128),
+        Err(Ok(TokenError::ForceTransferDisabled.into()))
+    );
+
+    client.set_force_transfer_enabled(&true);
+    client.set_force_transfer_enabled(&false);
+    assert_eq!(
+        client.try_force_transfer(&lost, &recovery, &1000i128),
+        Err(Ok(TokenError::ForceTransferDisabled.into()))
+    );
+    assert_eq!(client.balance(&lost), 1000i128);
+}
+
+#[test]
+fn test_force_transfer_rejects_contract_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.transfer_tranched(&user1, &user2, &1000i128, &2u32, &100u32);
+    client.set_force_transfer_enabled(&true);
+
+    assert_eq!(
+        client.try_force_transfer(&client.address, &admin, &1000i128),
+        Err(Ok(GuardError::ContractReserve.into()))
+    );
+    assert_eq!(client.balance(&client.address), 1000i128);
+}
+
+#[test]
+fn test_admin_transfer_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&admin, &1000i128);
+    let mark = event_mark(&env);
+    client.admin_transfer_batch(&admin, &vec![&env, user1.clone(), user2.clone()], &vec![&env, 300i128, 200i128]);
+    assert_events_since(&env, mark, &["transfer", "transfer"]);
+
+    // Only the admin authorized the distribution
+    assert_eq!(env.auths().len(), 1);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.balance(&admin), 500i128);
+    assert_eq!(client.balance(&user1), 300i128);
+    assert_eq!(client.balance(&user2), 200i128);
+
+    assert_eq!(
+        client.try_admin_transfer_batch(&admin, &vec![&env, user1.clone()], &vec![&env, 1i128, 2i128]),
+        Err(Ok(GuardError::BatchLengthMismatch.into()))
+    );
+}
+
+#[test]
+fn test_admin_transfer_batch_over_balance_reverts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&admin, &1000i128);
+    assert_eq!(
+        client.try_admin_transfer_batch(
+            &admin,
+            &vec![&env, user1.clone(), user2.clone()],
+            &vec![&env, 600i128, 401i128]
+        ),
+        Err(Ok(TokenError::InsufficientBalance.into()))
+    );
+
+    // Nothing moved, not even the entry that fit
+    assert_eq!(client.balance(&admin), 1000i128);
+    assert_eq!(client.balance(&user1), 0i128);
+    assert_eq!(client.balance(&user2), 0i128);
+}
+
+#[test]
+fn test_admin_transfer_batch_from_other_accounts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&holder, &1000i128);
+    assert_eq!(
+        client.try_admin_transfer_batch(&holder, &vec![&env, user.clone()], &vec![&env, 100i128]),
+        Err(Ok(TokenError::ForceTransferDisabled.into()))
+    );
+
+    client.set_force_transfer_enabled(&true);
+    client.admin_transfer_batch(&holder, &vec![&env, user.clone()], &vec![&env, 100i128]);
+    assert_eq!(client.balance(&holder), 900i128);
+    assert_eq!(client.balance(&user), 100i128);
+
+    // The vesting escrow is never a source, even with force transfers enabled
+    client.mint(&admin, &500i128);
+    client.create_vesting(&user, &500i128, &0u32, &100u32);
+    assert_eq!(
+        client.try_admin_transfer_batch(&client.address, &vec![&env, admin.clone()], &vec![&env, 500i128]),
+        Err(Ok(GuardError::ContractReserve.into()))
+    );
+    assert_eq!(client.balance(&client.address), 500i128);
+}
+
+#[test]
+fn test_max_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    assert_eq!(client.max_transfer(), 0i128);
+
+    client.mint(&user1, &10_000i128);
+    client.transfer(&user1, &user2, &100i128);
+    assert_eq!(client.max_transfer(), 100i128);
+
+    client.transfer(&user1, &user2, &500i128);
+    assert_eq!(client.max_transfer(), 500i128);
+
+    // Smaller transfers leave the maximum alone
+    client.transfer(&user1, &user2, &200i128);
+    assert_eq!(client.max_transfer(), 500i128);
+
+    client.approve(&user1, &spender, &2000i128, &1000u32);
+    client.transfer_from(&spender, &user1, &user2, &2000i128);
+    assert_eq!(client.max_transfer(), 2000i128);
+}
+
+#[test]
+fn test_transfer_dual_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    client.mint(&user1, &1000i128);
+
+    let sender_auth = MockAuth {
+        address: &user1,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "transfer_dual_auth",
+            args: (&user1, &user2, 300i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    };
+    let recipient_auth = MockAuth {
+        address: &user2,
+        invoke: sender_auth.invoke,
+    };
+
+    // The sender alone can't push the transfer through
+    assert!(client
+        .mock_auths(core::slice::from_ref(&sender_auth))
+        .try_transfer_dual_auth(&user1, &user2, &300i128)
+        .is_err());
+
+    // Nor can the recipient alone
+    assert!(client
+        .mock_auths(core::slice::from_ref(&recipient_auth))
+        .try_transfer_dual_auth(&user1, &user2, &300i128)
+        .is_err());
+
+    client
+        .mock_auths(&[sender_auth, recipient_auth])
+        .transfer_dual_auth(&user1, &user2, &300i128);
+    assert_eq!(client.balance(&user1), 700i128);
+    assert_eq!(client.balance(&user2), 300i128);
+
+    // Sending to oneself needs only the one auth
+    client
+        .mock_auths(&[MockAuth {
+            address: &user1,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "transfer_dual_auth",
+                args: (&user1, &user1, 100i128).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .transfer_dual_auth(&user1, &user1, &100i128);
+    assert_eq!(client.balance(&user1), 700i128);
+}
+
+#[test]
+fn test_reject_contract_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let token = client.address.clone();
+
+    client.mint(&user1, &1000i128);
+
+    // Off by default
+    client.transfer(&user1, &token, &100i128);
+    assert_eq!(client.balance(&token), 100i128);
+
+    client.set_reject_contract_recipient(&true);
+    assert!(client.try_transfer(&user1, &token, &100i128).is_err());
+    assert!(client.try_mint(&token, &100i128).is_err());
+
+    client.approve(&user1, &spender, &100i128, &1000u32);
+    assert!(client.try_transfer_from(&spender, &user1, &token, &100i128).is_err());
+    assert_eq!(client.balance(&token), 100i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")] // ContractRecipient
+fn test_transfer_to_token_contract_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_reject_contract_recipient(&true);
+    client.transfer(&user1, &client.address, &100i128);
+}
+
+#[test]
+fn test_reject_mint_to_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let token = client.address.clone();
+
+    // Minting into the reserve is allowed by default and stays out of circulation
+    client.mint(&token, &100i128);
+    assert_eq!(client.total_supply(), 100i128);
+    assert_eq!(client.circulating_supply(), 0i128);
+
+    client.set_reject_mint_to_contract(&true);
+    assert_eq!(
+        client.try_mint(&token, &100i128),
+        Err(Ok(TokenError::ContractRecipient.into()))
+    );
+
+    // Transfers to the contract are unaffected
+    client.mint(&user1, &100i128);
+    client.transfer(&user1, &token, &50i128);
+    assert_eq!(client.balance(&token), 150i128);
+}
+
+#[test]
+fn test_mint_limit_per_epoch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    env.ledger().with_mut(|li| li.sequence_number = 1000);
+    client.set_mint_limit(&Some(1000i128), &100u32);
+
+    client.mint(&user1, &600i128);
+    advance_ledger(&env, 50);
+    client.mint(&user1, &400i128);
+    assert!(client.try_mint(&user1, &1i128).is_err());
+
+    // The limit resets once the next epoch starts
+    advance_ledger(&env, 50);
+    client.mint(&user1, &1000i128);
+    assert!(client.try_mint(&user1, &1i128).is_err());
+    assert_eq!(client.total_supply(), 2000i128);
+
+    // Lifting the limit allows minting again immediately
+    client.set_mint_limit(&None, &0u32);
+    client.mint(&user1, &5000i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")] // EpochMintLimitExceeded
+fn test_mint_limit_exceeded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_mint_limit(&Some(1000i128), &100u32);
+    client.mint(&user1, &1001i128);
+}
+
+#[test]
+fn test_operator_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&owner, &1000i128);
+    assert!(!client.is_operator(&owner, &operator));
+    assert!(client.try_transfer_from(&operator, &owner, &user2, &100i128).is_err());
+
+    client.set_operator(&owner, &operator, &true);
+    assert!(client.is_operator(&owner, &operator));
+
+    // No numeric allowance is needed or consumed
+    client.transfer_from(&operator, &owner, &user2, &600i128);
+    client.transfer_from(&operator, &owner, &user2, &300i128);
+    assert_eq!(client.balance(&user2), 900i128);
+    assert_eq!(client.allowance(&owner, &operator), 0i128);
+
+    client.set_operator(&owner, &operator, &false);
+    assert!(!client.is_operator(&owner, &operator));
+    assert!(client.try_transfer_from(&operator, &owner, &user2, &100i128).is_err());
+}
+
+#[test]
+fn test_max_transfers_per_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &1000i128, &1000u32);
+    client.set_max_transfers_per_ledger(&Some(2u32));
+
+    client.transfer(&user1, &user2, &10i128);
+    client.transfer_from(&spender, &user1, &user2, &10i128);
+    assert!(client.try_transfer(&user1, &user2, &10i128).is_err());
+    assert!(client.try_transfer_from(&spender, &user1, &user2, &10i128).is_err());
+
+    // The count resets with the next ledger
+    advance_ledger(&env, 1);
+    client.transfer(&user1, &user2, &10i128);
+    client.transfer(&user1, &user2, &10i128);
+    assert_eq!(client.balance(&user2), 40i128);
+}
+
+#[test]
+fn test_max_transfer_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &1000i128, &1000u32);
+    client.set_max_transfer_amount(&Some(100i128));
+
+    // Transfers right at the cap go through
+    client.transfer(&user1, &user2, &100i128);
+    client.transfer_from(&spender, &user1, &user2, &100i128);
+    assert_eq!(client.balance(&user2), 200i128);
+
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &101i128),
+        Err(Ok(TokenError::ExceedsMaxTransfer.into()))
+    );
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &101i128),
+        Err(Ok(TokenError::ExceedsMaxTransfer.into()))
+    );
+
+    // Lifting the cap restores large transfers
+    client.set_max_transfer_amount(&None);
+    client.transfer(&user1, &user2, &500i128);
+    assert_eq!(client.balance(&user2), 700i128);
+}
+
+#[test]
+fn test_min_transfer_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &1000i128, &1000u32);
+    client.set_min_transfer_amount(&Some(10i128));
+    assert_eq!(client.config().min_transfer_amount, Some(10i128));
+
+    // Transfers right at the threshold go through
+    client.transfer(&user1, &user2, &10i128);
+    client.transfer_from(&spender, &user1, &user2, &10i128);
+    assert_eq!(client.balance(&user2), 20i128);
+
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &9i128),
+        Err(Ok(GuardError::BelowMinTransfer.into()))
+    );
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &9i128),
+        Err(Ok(GuardError::BelowMinTransfer.into()))
+    );
+
+    // Zero is not dust; it stays a no-op unless zero transfers are rejected
+    client.transfer(&user1, &user2, &0i128);
+    client.set_reject_zero_transfers(&true);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &0i128),
+        Err(Ok(TokenError::ZeroAmount.into()))
+    );
+    assert_eq!(client.balance(&user2), 20i128);
+
+    assert_eq!(
+        client.try_set_min_transfer_amount(&Some(0i128)),
+        Err(Ok(TokenError::InvalidAmount.into()))
+    );
+}
+
+#[test]
+fn test_negative_transfer_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.mint(&user2, &1000i128);
+
+    // A negative amount must never pull tokens from the recipient
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &-500i128),
+        Err(Ok(TokenError::InvalidAmount.into()))
+    );
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert_eq!(client.balance(&user2), 1000i128);
+}
+
+#[test]
+fn test_max_transfer_amount_admin_exempt() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&admin, &1000i128);
+    client.set_max_transfer_amount(&Some(100i128));
+
+    client.transfer(&admin, &user1, &600i128);
+    assert_eq!(client.balance(&user1), 600i128);
+
+    assert_eq!(
+        client.try_set_max_transfer_amount(&Some(0i128)),
+        Err(Ok(TokenError::InvalidAmount.into()))
+    );
+}
+
+#[test]
+fn test_max_wallet_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &1000i128, &1000u32);
+    client.set_max_wallet_balance(&Some(500i128));
+
+    // Filling a wallet exactly to the cap is fine
+    client.mint(&user2, &300i128);
+    client.transfer(&user1, &user2, &200i128);
+
+    assert_eq!(
+        client.try_mint(&user2, &1i128),
+        Err(Ok(TokenError::ExceedsWalletLimit.into()))
+    );
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &1i128),
+        Err(Ok(TokenError::ExceedsWalletLimit.into()))
+    );
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &1i128),
+        Err(Ok(TokenError::ExceedsWalletLimit.into()))
+    );
+    assert_eq!(client.balance(&user2), 500i128);
+}
+
+#[test]
+fn test_wallet_limit_exempt() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_max_wallet_balance(&Some(500i128));
+    client.set_wallet_limit_exempt(&treasury, &true);
+    client.mint(&treasury, &5000i128);
+    assert_eq!(client.balance(&treasury), 5000i128);
+
+    // Removing the exemption applies the cap to further receipts
+    client.set_wallet_limit_exempt(&treasury, &false);
+    assert_eq!(
+        client.try_mint(&treasury, &1i128),
+        Err(Ok(TokenError::ExceedsWalletLimit.into()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")] // ThroughputCapReached
+fn test_throughput_cap_reached() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_max_transfers_per_ledger(&Some(1u32));
+
+    client.transfer(&user1, &user2, &10i128);
+    client.transfer(&user1, &user2, &10i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")] // NotInitialized
+fn test_mint_before_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let client = register_token(&env);
+
+    client.mint(&user, &100i128);
+}
+
+#[test]
+fn test_balance_formatted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.balance_formatted(&user1), (0, 0));
+
+    client.mint(&user1, &12345678i128);
+    client.mint(&user2, &42i128);
+    client.mint(&user3, &30000000i128);
+
+    assert_eq!(client.balance_formatted(&user1), (1, 2345678));
+    assert_eq!(client.balance_formatted(&user2), (0, 42));
+    assert_eq!(client.balance_formatted(&user3), (3, 0));
+}
+
+fn create_ruled_token<'a>(env: &Env, admin: &Address, allowed: &Address) -> TokenContractClient<'a> {
+    let client = create_token(env, admin);
+    client.set_rules(&vec![
+        env,
+        Rule::MinAmount(100),
+        Rule::Allowlist(vec![env, allowed.clone()]),
+    ]);
+    client
+}
+
+#[test]
+fn test_rules_pass() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_ruled_token(&env, &admin, &user2);
+
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user2, &100i128);
+    assert_eq!(client.balance(&user2), 100i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #32)")] // RuleMinAmount
+fn test_rules_report_min_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    let client = create_ruled_token(&env, &admin, &user2);
+
+    client.mint(&user1, &1000i128);
+    // Fails both rules; the first one in the list is reported
+    client.transfer(&user1, &user3, &50i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #33)")] // RuleAllowlist
+fn test_rules_report_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    let client = create_ruled_token(&env, &admin, &user2);
+
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user3, &150i128);
+}
+
+#[test]
+fn test_rules_cooldown() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_rules(&vec![&env, Rule::Cooldown(10)]);
+
+    client.transfer(&user1, &user2, &10i128);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &10i128),
+        Err(Ok(TokenError::RuleCooldown.into()))
+    );
+
+    advance_ledger(&env, 10);
+    client.transfer(&user1, &user2, &10i128);
+    assert_eq!(client.balance(&user2), 20i128);
+}
+
+#[test]
+fn test_transfer_cooldown() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &1000i128, &1000u32);
+    client.set_transfer_cooldown(&10u32);
+
+    client.transfer(&user1, &user2, &100i128);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &100i128),
+        Err(Ok(GuardError::TransferCooldown.into()))
+    );
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &100i128),
+        Err(Ok(GuardError::TransferCooldown.into()))
+    );
+
+    // The recipient is not affected, and the sender may move again once it elapses
+    client.transfer(&user2, &admin, &10i128);
+    advance_ledger(&env, 10);
+    client.transfer_from(&spender, &user1, &user2, &100i128);
+    assert_eq!(client.balance(&user1), 800i128);
+}
+
+#[test]
+fn test_transfer_cooldown_exempt() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let exchange = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&exchange, &1000i128);
+    client.set_transfer_cooldown(&10u32);
+    client.set_cooldown_exempt(&exchange, &true);
+
+    client.transfer(&exchange, &user1, &100i128);
+    client.transfer(&exchange, &user1, &100i128);
+    assert_eq!(client.balance(&user1), 200i128);
+}
+
+#[test]
+fn test_minter_remaining() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = register_token(&env);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &Some(1_500i128),
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+    client.set_mint_limit(&Some(1_000i128), &100u32);
+
+    assert_eq!(client.minter_remaining(&admin), 1_000i128);
+    assert_eq!(client.minter_remaining(&user), 0i128);
+
+    client.mint(&user, &400i128);
+    assert_eq!(client.minter_remaining(&admin), 600i128);
+
+    // The epoch limit resets with the next epoch
+    advance_ledger(&env, 100);
+    assert_eq!(client.minter_remaining(&admin), 1_000i128);
+
+    // Near the supply cap the remaining supply is the tighter bound
+    client.mint(&user, &1_000i128);
+    advance_ledger(&env, 100);
+    assert_eq!(client.minter_remaining(&admin), 100i128);
+
+    // Nothing can be minted during a global freeze
+    client.set_frozen(&true);
+    assert_eq!(client.minter_remaining(&admin), 0i128);
+    client.set_frozen(&false);
+
+    // Under an admin set its members mint through proposals, and the old admin cannot
+    let admin1 = Address::generate(&env);
+    let admin2 = Address::generate(&env);
+    client.set_admins(&vec![&env, admin1.clone(), admin2.clone()], &2u32);
+    assert_eq!(client.minter_remaining(&admin1), 100i128);
+    assert_eq!(client.minter_remaining(&admin), 0i128);
+
+    let hash = client.propose_action(&admin1, &AdminAction::Mint(user.clone(), 40i128));
+    client.approve_action(&admin2, &hash);
+    assert_eq!(client.minter_remaining(&admin2), 60i128);
+}
+
+#[test]
+fn test_initialize_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let (contract, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(contract, client.address);
+    assert_eq!(topics, ("initialize",).into_val(&env));
+    let data: (Address, String, String, u32) = data.into_val(&env);
+    assert_eq!(
+        data,
+        (
+            admin,
+            String::from_str(&env, "Test Token"),
+            String::from_str(&env, "TEST"),
+            7u32,
+        )
+    );
+}
+
+#[test]
+fn test_event_schema_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    // Indexers key on this; bump it only alongside an event format change
+    assert_eq!(client.event_schema_version(), 3u32);
+    client.mint(&user1, &1000i128);
+    client.set_admin(&user1);
+    assert_eq!(client.event_schema_version(), 3u32);
+}
+
+#[test]
+fn test_reversible_transfer_finalize() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    assert!(client.try_transfer_reversible(&user1, &user2, &100i128).is_err());
+
+    client.set_reversal_window(&10u32, &None);
+    let id = client.transfer_reversible(&user1, &user2, &100i128);
+    assert_eq!(client.balance(&user1), 900i128);
+    assert_eq!(client.balance(&user2), 0i128);
+    assert_eq!(client.balance(&client.address), 100i128);
+
+    assert!(client.try_finalize_transfer(&id).is_err());
+
+    advance_ledger(&env, 10);
+    assert!(client.try_reverse_transfer(&user1, &id).is_err());
+    client.finalize_transfer(&id);
+    assert_eq!(client.balance(&user2), 100i128);
+    assert_eq!(client.balance(&client.address), 0i128);
+    assert!(client.pending_transfer(&id).is_none());
+    assert!(client.try_finalize_transfer(&id).is_err());
+}
+
+#[test]
+fn test_reversible_transfer_reverse() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_reversal_window(&10u32, &Some(arbiter.clone()));
+
+    // The sender can reverse within the window
+    let id = client.transfer_reversible(&user1, &user2, &100i128);
+    advance_ledger(&env, 5);
+    assert!(client.try_reverse_transfer(&user2, &id).is_err());
+    client.reverse_transfer(&user1, &id);
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert!(client.pending_transfer(&id).is_none());
+
+    // So can the arbiter
+    let id = client.transfer_reversible(&user1, &user2, &200i128);
+    client.reverse_transfer(&arbiter, &id);
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert_eq!(client.balance(&user2), 0i128);
+    assert_eq!(client.balance(&client.address), 0i128);
+}
+
+#[test]
+fn test_reversible_transfer_applies_transfer_policy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_reversal_window(&10u32, &None);
+
+    // The fee is charged when the transfer is made, and only the rest is held
+    client.set_transfer_fee(&100u32, &Some(collector.clone()));
+    let id = client.transfer_reversible(&user1, &user2, &200i128);
+    assert_eq!(client.balance(&collector), 2i128);
+    assert_eq!(client.pending_transfer(&id).unwrap().amount, 198i128);
+
+    // The recipient's wallet cap applies on delivery
+    client.set_max_wallet_balance(&Some(100i128));
+    advance_ledger(&env, 10);
+    assert_eq!(
+        client.try_finalize_transfer(&id),
+        Err(Ok(TokenError::ExceedsWalletLimit.into()))
+    );
+    client.set_max_wallet_balance(&None);
+    client.finalize_transfer(&id);
+    assert_eq!(client.balance(&user2), 198i128);
+
+    client.set_allowlist_enabled(&true);
+    assert_eq!(
+        client.try_transfer_reversible(&user1, &user2, &100i128),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+
+    // A window reaching past the last ledger saturates instead of overflowing
+    client.set_allowlist_enabled(&false);
+    client.set_reversal_window(&u32::MAX, &None);
+    let id = client.transfer_reversible(&user1, &user2, &100i128);
+    assert_eq!(client.pending_transfer(&id).unwrap().finalizes_at, u32::MAX);
+}
+
+#[test]
+fn test_transfer_from_matches_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_transfer_fee(&100u32, &Some(collector.clone()));
+    client.approve(&user1, &spender, &500i128, &1000u32);
+
+    client.transfer(&user1, &user2, &200i128);
+    client.transfer_from(&spender, &user1, &user2, &200i128);
+    assert_eq!(client.balance(&user2), 396i128);
+    assert_eq!(client.balance(&collector), 4i128);
+
+    // A delegated self-transfer moves nothing and pays no fee, like a direct one
+    client.transfer_from(&spender, &user1, &user1, &100i128);
+    assert_eq!(client.balance(&user1), 600i128);
+    assert_eq!(client.balance(&collector), 4i128);
+    assert_eq!(client.allowance(&user1, &spender), 200i128);
+}
+
+#[test]
+fn test_frozen_spender_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &500i128, &1000u32);
+    client.freeze(&spender, &String::from_str(&env, "compliance"));
+
+    assert!(client.try_transfer_from(&spender, &user1, &user2, &100i128).is_err());
+    assert_eq!(client.allowance(&user1, &spender), 500i128);
+
+    client.unfreeze(&spender);
+    client.transfer_from(&spender, &user1, &user2, &100i128);
+    assert_eq!(client.balance(&user2), 100i128);
+}
+
+#[test]
+fn test_supply_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.supply_history(&0u32, &MAX_SUPPLY_HISTORY).len(), 0);
+
+    client.mint(&user1, &1000i128);
+    advance_ledger(&env, 5);
+    client.admin_burn(&user1, &300i128);
+    advance_ledger(&env, 5);
+    client.burn_self(&user1, &200i128);
+
+    let start = env.ledger().sequence() - 10;
+    assert_eq!(
+        client.supply_history(&0u32, &MAX_SUPPLY_HISTORY),
+        vec![
+            &env,
+            (start, 1000i128, 1000i128),
+            (start + 5, -300i128, 700i128),
+            (start + 10, -200i128, 500i128),
+        ]
+    );
+}
+
+#[test]
+fn test_supply_history_pages() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    for _ in 0..5 {
+        client.mint(&user1, &10i128);
+    }
+    let totals = |start: u32, limit: u32| {
+        let mut totals = std::vec::Vec::new();
+        for (_, _, total) in client.supply_history(&start, &limit).iter() {
+            totals.push(total);
+        }
+        totals
+    };
+
+    assert_eq!(totals(0, 2), [10i128, 20]);
+    assert_eq!(totals(2, 2), [30i128, 40]);
+    assert_eq!(totals(4, 2), [50i128]);
+    assert!(totals(5, 2).is_empty());
+    assert!(totals(0, 0).is_empty());
+}
+
+#[test]
+fn test_supply_history_is_bounded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    for _ in 0..MAX_SUPPLY_HISTORY + 5 {
+        client.mint(&user1, &1i128);
+    }
+
+    // Only the most recent entries are kept
+    let history = client.supply_history(&0u32, &MAX_SUPPLY_HISTORY);
+    assert_eq!(history.len(), MAX_SUPPLY_HISTORY);
+    assert_eq!(history.first().unwrap().2, 6i128);
+    assert_eq!(history.last().unwrap().2, (MAX_SUPPLY_HISTORY + 5) as i128);
+}
+
+#[test]
+fn test_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_transfer_fee(&50u32, &Some(collector.clone()));
+    client.set_max_transfer_amount(&Some(500i128));
+    client.set_max_supply(&Some(1_000_000i128));
+    client.set_reject_zero_transfers(&true);
+
+    let config = client.config();
+    assert_eq!(config.is_mintable, client.is_mintable());
+    assert_eq!(config.is_burnable, client.is_burnable());
+    assert_eq!(config.is_freezable, client.is_freezable());
+    assert_eq!(config.max_supply, client.max_supply());
+    assert_eq!(config.max_supply, Some(1_000_000i128));
+    assert_eq!(config.fee_bps, 50u32);
+    assert_eq!(config.fee_collector, Some(collector));
+    assert_eq!(config.max_transfer_amount, Some(500i128));
+    assert_eq!(config.max_wallet_balance, None);
+    assert!(config.reject_zero_transfers);
+    assert!(!config.is_frozen);
+
+    client.disable_freezing();
+    assert!(!client.config().is_freezable);
+}
+
+#[test]
+fn test_economics() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_mint_limit(&Some(1000i128), &100u32);
+    advance_ledger(&env, 100);
+    client.mint(&user2, &100i128);
+    client.admin_burn(&user2, &30i128);
+    client.burn_self(&user1, &20i128);
+    // Tranched tokens sit in the contract's reserve until claimed
+    client.transfer_tranched(&user1, &user2, &200i128, &2u32, &10u32);
+
+    let economics = client.economics();
+    assert_eq!(economics.total_supply, client.total_supply());
+    assert_eq!(economics.total_supply, 1050i128);
+    assert_eq!(economics.circulating_supply, 850i128);
+    assert_eq!(economics.max_supply, client.max_supply());
+    assert_eq!(economics.total_minted, 1100i128);
+    assert_eq!(economics.total_burned, 50i128);
+    assert_eq!(economics.holder_count, client.total_holders());
+    // 100 minted this epoch on top of the 950 supply before it
+    assert_eq!(economics.inflation_bps, 1052i128);
+}
+
+#[test]
+fn test_economics_inflation_with_burns() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_mint_limit(&Some(1000i128), &100u32);
+    assert_eq!(client.economics().inflation_bps, 0i128);
+
+    // Everything minted this epoch was burned again, leaving no prior supply
+    client.mint(&user, &100i128);
+    client.burn_self(&user, &100i128);
+    assert_eq!(client.total_supply(), 0i128);
+    assert_eq!(client.economics().inflation_bps, 0i128);
+
+    client.mint(&user, &50i128);
+    client.burn_self(&user, &20i128);
+    assert_eq!(client.economics().inflation_bps, 0i128);
+
+    // Supply from an earlier epoch gives the ratio a base again
+    advance_ledger(&env, 100);
+    client.mint(&user, &3i128);
+    assert_eq!(client.economics().inflation_bps, 1000i128);
+}
+
+#[test]
+fn test_error_codes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = register_token(&env);
+
+    assert_eq!(
+        client.try_mint(&user1, &100i128),
+        Err(Ok(TokenError::NotInitialized.into()))
+    );
+
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &Some(1000i128),
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+    assert_eq!(
+        client.try_initialize(
+            &admin,
+            &7u32,
+            &String::from_str(&env, "Test Token"),
+            &String::from_str(&env, "TEST"),
+            &None,
+            &true,
+            &true,
+            &true,
+            &None,
+        ),
+        Err(Ok(TokenError::AlreadyInitialized.into()))
+    );
+
+    client.mint(&user1, &100i128);
+    assert_eq!(
+        client.try_mint(&user1, &1000i128),
+        Err(Ok(TokenError::ExceedsMaxSupply.into()))
+    );
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &500i128),
+        Err(Ok(TokenError::InsufficientBalance.into()))
+    );
+
+    client.freeze(&user1, &String::from_str(&env, "compliance"));
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &10i128),
+        Err(Ok(TokenError::Frozen.into()))
+    );
+
+    client.set_frozen(&true);
+    assert_eq!(
+        client.try_transfer(&user2, &user1, &10i128),
+        Err(Ok(TokenError::GloballyFrozen.into()))
+    );
+}
+
+#[test]
+fn test_mint_overflow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user, &i128::MAX);
+    assert_eq!(
+        client.try_mint(&user, &1i128),
+        Err(Ok(TokenError::Overflow.into()))
+    );
+    assert_eq!(client.total_supply(), i128::MAX);
+}
+
+#[test]
+fn test_mint_overflow_without_max_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    assert_eq!(client.max_supply(), None);
+
+    client.mint(&user1, &(i128::MAX - 10));
+    assert_eq!(
+        client.try_mint(&user2, &11i128),
+        Err(Ok(TokenError::Overflow.into()))
+    );
+    assert_eq!(client.balance(&user2), 0i128);
+
+    // Filling the supply exactly to the ceiling is still allowed
+    client.mint(&user2, &10i128);
+    assert_eq!(client.total_supply(), i128::MAX);
+}
+
+#[test]
+fn test_negative_mint_and_burn_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    assert_eq!(
+        client.try_mint(&user1, &-1i128),
+        Err(Ok(TokenError::InvalidAmount.into()))
+    );
+    assert_eq!(
+        client.try_burn_self(&user1, &-1i128),
+        Err(Ok(TokenError::InvalidAmount.into()))
+    );
+    assert_eq!(
+        client.try_admin_burn(&user1, &-1i128),
+        Err(Ok(TokenError::InvalidAmount.into()))
+    );
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert_eq!(client.total_supply(), 1000i128);
+}
+
+#[test]
+fn test_not_mintable_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = register_token(&env);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &false,
+        &false,
+        &false,
+        &None,
+    );
+
+    assert_eq!(
+        client.try_mint(&user, &100i128),
+        Err(Ok(TokenError::NotMintable.into()))
+    );
+    assert_eq!(
+        client.try_admin_burn(&user, &100i128),
+        Err(Ok(TokenError::NotBurnable.into()))
+    );
+    assert_eq!(
+        client.try_freeze(&user, &String::from_str(&env, "compliance")),
+        Err(Ok(TokenError::NotFreezable.into()))
+    );
+}
+
+fn try_initialize_with_cap(env: &Env, max_supply: Option<i128>) -> bool {
+    let admin = Address::generate(env);
+    let client = register_token(env);
+    let result = client.try_initialize(
+        &admin,
+        &7u32,
+        &String::from_str(env, "Test Token"),
+        &String::from_str(env, "TEST"),
+        &max_supply,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+    match result {
+        Ok(_) => true,
+        Err(err) => {
+            assert_eq!(err, Ok(TokenError::InvalidConfig.into()));
+            false
+        }
+    }
+}
+
+#[test]
+fn test_initialize_max_supply_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    assert!(!try_initialize_with_cap(&env, Some(0i128)));
+    assert!(!try_initialize_with_cap(&env, Some(-1i128)));
+    assert!(try_initialize_with_cap(&env, Some(1000i128)));
+    assert!(try_initialize_with_cap(&env, None));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")] // NotMintable
+fn test_mint_on_non_mintable_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = register_token(&env);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &false,
+        &true,
+        &true,
+        &None,
+    );
+
+    client.mint(&user, &100i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")] // NotBurnable
+fn test_burn_on_non_burnable_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = register_token(&env);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &false,
+        &true,
+        &None,
+    );
+
+    client.mint(&user, &100i128);
+    client.burn_self(&user, &100i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")] // NotFreezable
+fn test_freeze_on_non_freezable_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = register_token(&env);
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &false,
+        &None,
+    );
+
+    client.freeze(&user, &String::from_str(&env, "compliance"));
+}
+
+#[test]
+fn test_initialize_with_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = register_token(&env);
+    let mark = event_mark(&env);
+
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &Some(2_000_000i128),
+        &true,
+        &true,
+        &true,
+        &Some(InitialSupply {
+            recipient: treasury.clone(),
+            amount: 1_000_000i128,
+        }),
+    );
+    assert_events_since(&env, mark, &["initialize", "mint"]);
+
+    assert_eq!(client.balance(&treasury), 1_000_000i128);
+    assert_eq!(client.total_supply(), 1_000_000i128);
+    assert_eq!(client.total_holders(), 1u64);
+    assert_eq!(client.economics().total_minted, 1_000_000i128);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("mint", admin, treasury).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 1_000_000i128);
+}
+
+#[test]
+fn test_deploy_basic_matches_manual_setup() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let name = String::from_str(&env, "Test Token");
+    let symbol = String::from_str(&env, "TEST");
+
+    let basic = register_token(&env);
+    basic.deploy_basic(&admin, &7u32, &name, &symbol, &1_000_000i128, &true);
+
+    let manual = register_token(&env);
+    manual.initialize(&admin, &7u32, &name, &symbol, &None, &true, &true, &false, &None);
+    manual.mint(&admin, &1_000_000i128);
+    manual.renounce_minting();
+
+    for client in [&basic, &manual] {
+        assert_eq!(client.balance(&admin), 1_000_000i128);
+        assert_eq!(client.total_supply(), 1_000_000i128);
+        assert_eq!(client.economics().total_minted, 1_000_000i128);
+        assert_eq!(client.metadata(), (name.clone(), symbol.clone(), 7u32));
+        assert!(!client.is_mintable());
+        assert!(client.is_burnable());
+        assert!(!client.is_freezable());
+    }
+    assert_eq!(basic.supply_history(&0u32, &10u32), manual.supply_history(&0u32, &10u32));
+}
+
+#[test]
+fn test_deploy_basic_without_renounce() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+    client.deploy_basic(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &1000i128,
+        &false,
+    );
+
+    assert!(client.is_mintable());
+    assert_eq!(client.mint(&admin, &500i128), 1500i128);
+}
+
+#[test]
+fn test_initialize_without_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.total_supply(), 0i128);
+    assert_eq!(client.total_holders(), 0u64);
+}
+
+#[test]
+fn test_initialize_supply_exceeds_max() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = register_token(&env);
+
+    assert_eq!(
+        client.try_initialize(
+            &admin,
+            &7u32,
+            &String::from_str(&env, "Test Token"),
+            &String::from_str(&env, "TEST"),
+            &Some(1_000i128),
+            &true,
+            &true,
+            &true,
+            &Some(InitialSupply {
+                recipient: treasury.clone(),
+                amount: 1_001i128,
+            }),
+        ),
+        Err(Ok(TokenError::ExceedsMaxSupply.into()))
+    );
+    assert_eq!(client.balance(&treasury), 0i128);
+}
+
+#[test]
+fn test_is_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+    assert!(!client.is_initialized());
+
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+    assert!(client.is_initialized());
+}
+
+#[test]
+fn test_prune_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let expiration = env.ledger().sequence() + 2;
+    client.approve(&user, &spender, &500i128, &expiration);
+
+    // Still live: pruning leaves it alone and emits nothing
+    let events_before = env.events().all().len();
+    client.prune_allowance(&user, &spender);
+    assert_eq!(env.events().all().len(), events_before);
+    assert_eq!(client.allowance_detail(&user, &spender), (500i128, expiration));
+
+    advance_ledger(&env, 3);
+    assert_eq!(client.allowance_detail(&user, &spender), (0i128, expiration));
+
+    client.prune_allowance(&user, &spender);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("prune_allowance",).into_val(&env));
+    let data: (Address, Address) = data.into_val(&env);
+    assert_eq!(data, (user.clone(), spender.clone()));
+    assert_eq!(client.allowance_detail(&user, &spender), (0i128, 0u32));
+
+    // The pruned spender is no longer tracked, so there is nothing left to revoke
+    assert_eq!(client.revoke_all_allowances(&user), 0u32);
+}
+
+#[test]
+fn test_prune_allowances_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let short = Address::generate(&env);
+    let long = Address::generate(&env);
+    let never = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let sequence = env.ledger().sequence();
+    client.approve(&user, &short, &100i128, &(sequence + 2));
+    client.approve(&user, &long, &200i128, &(sequence + 1000));
+    client.approve(&admin, &short, &300i128, &(sequence + 2));
+    advance_ledger(&env, 3);
+
+    // Two expired, one live, one that never existed
+    let pairs = vec![
+        &env,
+        (user.clone(), short.clone()),
+        (user.clone(), long.clone()),
+        (admin.clone(), short.clone()),
+        (user.clone(), never.clone()),
+    ];
+    assert_eq!(client.prune_allowances_batch(&pairs), 2u32);
+    assert_eq!(client.allowance_detail(&user, &short), (0i128, 0u32));
+    assert_eq!(client.allowance_detail(&admin, &short), (0i128, 0u32));
+    assert_eq!(client.allowance(&user, &long), 200i128);
+
+    // Running the sweep again finds nothing left to prune
+    assert_eq!(client.prune_allowances_batch(&pairs), 0u32);
+
+    // Only the live spender is still tracked
+    assert_eq!(client.revoke_all_allowances(&user), 1u32);
+    assert_eq!(client.revoke_all_allowances(&admin), 0u32);
+}
+
+#[test]
+fn test_transfer_memo() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let exchange = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    let memo = String::from_str(&env, "deposit-123456");
+    client.transfer_memo(&user1, &exchange, &250i128, &memo);
+    assert_eq!(client.balance(&exchange), 250i128);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("transfer", user1.clone(), exchange.clone(), memo).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 250i128);
+
+    let long_memo = String::from_str(&env, &"x".repeat(65));
+    assert_eq!(
+        client.try_transfer_memo(&user1, &exchange, &250i128, &long_memo),
+        Err(Ok(TokenError::MemoTooLong.into()))
+    );
+    assert_eq!(client.balance(&exchange), 250i128);
+}
+
+#[test]
+fn test_transfer_keeps_supply_consistent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_transfer_fee(&100u32, &Some(collector.clone()));
+    client.approve(&user1, &spender, &300i128, &1000u32);
+
+    client.transfer(&user1, &user2, &200i128);
+    client.transfer_from(&spender, &user1, &user2, &300i128);
+
+    // A failed transfer leaves every balance untouched
+    assert!(client.try_transfer(&user2, &user1, &1000i128).is_err());
+    assert!(client.try_transfer_from(&spender, &user1, &user2, &1i128).is_err());
+
+    let balances = client.balance(&user1) + client.balance(&user2) + client.balance(&collector);
+    assert_eq!(balances, client.total_supply());
+    assert_eq!(client.balance(&user1), 500i128);
+    assert_eq!(client.balance(&user2), 495i128);
+    assert_eq!(client.balance(&collector), 5i128);
+    assert_eq!(client.allowance(&user1, &spender), 0i128);
+}
+
+#[test]
+fn test_receiver_hook() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let receiver_id = env.register_contract(None, MockReceiver);
+    let receiver = MockReceiverClient::new(&env, &receiver_id);
+
+    client.mint(&user, &1000i128);
+
+    // Hooks are off by default
+    client.transfer(&user, &receiver_id, &100i128);
+    assert_eq!(receiver.received().len(), 0);
+
+    client.set_hooks_enabled(&true);
+    client.transfer(&user, &receiver_id, &200i128);
+    client.approve(&user, &spender, &300i128, &1000u32);
+    client.transfer_from(&spender, &user, &receiver_id, &300i128);
+    assert_eq!(
+        receiver.received(),
+        vec![&env, (user.clone(), 200i128), (user.clone(), 300i128)]
+    );
+    assert_eq!(client.balance(&receiver_id), 600i128);
+}
+
+#[test]
+fn test_receiver_hook_not_implemented() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    // Neither contract implements on_token_received
+    let plain_id = env.register_contract(None, MockGuardian);
+    let failing_id = env.register_contract(None, FailingGuardian);
+
+    client.mint(&user, &1000i128);
+    client.set_hooks_enabled(&true);
+    client.transfer(&user, &plain_id, &100i128);
+    client.transfer(&user, &failing_id, &100i128);
+    assert_eq!(client.balance(&plain_id), 100i128);
+    assert_eq!(client.balance(&failing_id), 100i128);
+}
+
+#[test]
+fn test_receiver_hook_cannot_reenter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let receiver_id = env.register_contract(None, ReentrantReceiver);
+    let receiver = ReentrantReceiverClient::new(&env, &receiver_id);
+    receiver.set_token(&client.address);
+
+    client.mint(&user, &1000i128);
+    client.set_hooks_enabled(&true);
+    client.transfer(&user, &receiver_id, &100i128);
+
+    // The host rejected the hook's transfer back into the token
+    assert!(receiver.blocked());
+    assert_eq!(client.balance(&receiver_id), 100i128);
+    assert_eq!(client.balance(&user), 900i128);
+    client.transfer(&user, &admin, &100i128);
+}
+
+#[test]
+fn test_lock_partial_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.lock(&user1, &600i128);
+    assert_eq!(client.locked_balance(&user1), 600i128);
+    assert_eq!(
+        client.try_lock(&user1, &500i128),
+        Err(Ok(TokenError::InsufficientBalance.into()))
+    );
+
+    client.transfer(&user1, &user2, &400i128);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &1i128),
+        Err(Ok(TokenError::BalanceLocked.into()))
+    );
+    client.approve(&user1, &spender, &100i128, &1000u32);
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &100i128),
+        Err(Ok(TokenError::BalanceLocked.into()))
+    );
+    assert!(client.try_burn_self(&user1, &1i128).is_err());
+
+    assert_eq!(
+        client.try_unlock(&user1, &601i128),
+        Err(Ok(TokenError::InvalidAmount.into()))
+    );
+    client.unlock(&user1, &250i128);
+    client.transfer(&user1, &user2, &250i128);
+    assert_eq!(client.balance(&user1), 350i128);
+    assert_eq!(client.balance(&user2), 650i128);
+
+    client.unlock(&user1, &350i128);
+    assert_eq!(client.locked_balance(&user1), 0i128);
+    client.transfer(&user1, &user2, &350i128);
+    assert_eq!(client.balance(&user2), 1000i128);
+}
+
+#[test]
+fn test_circulating_supply_exclusions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&treasury, &7000i128);
+    client.mint(&user, &3000i128);
+    assert_eq!(client.circulating_supply(), 10000i128);
+
+    client.set_excluded_from_circulation(&treasury, &true);
+    assert_eq!(client.circulating_supply(), 3000i128);
+    assert_eq!(client.total_supply(), 10000i128);
+
+    // Balance changes on an excluded account keep the figure current
+    client.transfer(&treasury, &user, &1000i128);
+    client.mint(&treasury, &500i128);
+    assert_eq!(client.circulating_supply(), 4000i128);
+    assert_eq!(client.economics().circulating_supply, 4000i128);
+
+    client.set_excluded_from_circulation(&treasury, &false);
+    assert_eq!(client.circulating_supply(), client.total_supply());
+}
+
+#[test]
+fn test_linear_vesting() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&admin, &10_000i128);
+    let start = env.ledger().sequence() + 100;
+    client.create_vesting(&beneficiary, &1_000i128, &start, &1_000u32);
+    assert_eq!(client.balance(&admin), 9_000i128);
+    assert_eq!(client.balance(&client.address), 1_000i128);
+    assert_eq!(
+        client.try_create_vesting(&beneficiary, &1_000i128, &start, &1_000u32),
+        Err(Ok(TokenError::VestingExists.into()))
+    );
+
+    // Nothing vests before the start
+    assert_eq!(client.claim_vested(&beneficiary), 0i128);
+    advance_ledger(&env, 100);
+    assert_eq!(client.claim_vested(&beneficiary), 0i128);
+
+    // A quarter of the way through
+    advance_ledger(&env, 250);
+    assert_eq!(client.claim_vested(&beneficiary), 250i128);
+    assert_eq!(client.claim_vested(&beneficiary), 0i128);
+
+    advance_ledger(&env, 250);
+    assert_eq!(client.claim_vested(&beneficiary), 250i128);
+    assert_eq!(client.vesting_schedule(&beneficiary).unwrap().claimed, 500i128);
+
+    // Past the end the remainder is released and the schedule removed
+    advance_ledger(&env, 5_000);
+    assert_eq!(client.claim_vested(&beneficiary), 500i128);
+    assert_eq!(client.balance(&beneficiary), 1_000i128);
+    assert_eq!(client.balance(&client.address), 0i128);
+    assert!(client.vesting_schedule(&beneficiary).is_none());
+    assert_eq!(
+        client.try_claim_vested(&beneficiary),
+        Err(Ok(TokenError::VestingNotFound.into()))
+    );
+}
+
+#[test]
+fn test_vesting_applies_transfer_policy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&admin, &10_000i128);
+    client.set_allowlist_enabled(&true);
+    client.add_to_allowlist(&admin);
+    let start = env.ledger().sequence();
+    assert_eq!(
+        client.try_create_vesting(&beneficiary, &1_000i128, &start, &100u32),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+
+    // The schedule vests what is left after the transfer fee
+    client.add_to_allowlist(&beneficiary);
+    client.add_to_allowlist(&collector);
+    client.set_transfer_fee(&100u32, &Some(collector.clone()));
+    client.create_vesting(&beneficiary, &1_000i128, &start, &100u32);
+    assert_eq!(client.vesting_schedule(&beneficiary).unwrap().total, 990i128);
+    assert_eq!(client.balance(&collector), 10i128);
+
+    // A beneficiary dropped from the allowlist cannot claim
+    advance_ledger(&env, 100);
+    client.remove_from_allowlist(&beneficiary);
+    assert_eq!(
+        client.try_claim_vested(&beneficiary),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+    client.add_to_allowlist(&beneficiary);
+    assert_eq!(client.claim_vested(&beneficiary), 990i128);
+}
+
+#[test]
+fn test_require_opt_in() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    // With the mode off nobody needs to opt in
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user2, &100i128);
+
+    client.set_require_opt_in(&true);
+    assert!(client.config().require_opt_in);
+    assert_eq!(
+        client.try_transfer(&user2, &user1, &10i128),
+        Err(Ok(GuardError::NotOptedIn.into()))
+    );
+    assert_eq!(
+        client.try_mint(&user2, &10i128),
+        Err(Ok(GuardError::NotOptedIn.into()))
+    );
+
+    // Only the recipient has to opt in; the sender never did
+    client.opt_in(&user1);
+    assert!(client.is_opted_in(&user1));
+    assert!(!client.is_opted_in(&user2));
+    client.transfer(&user2, &user1, &10i128);
+    client.mint(&user1, &10i128);
+    assert_eq!(client.balance(&user1), 920i128);
+
+    client.set_require_opt_in(&false);
+    client.transfer(&user1, &user2, &20i128);
+    assert_eq!(client.balance(&user2), 110i128);
+}
+
+#[test]
+fn test_allowlist_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    // With the mode off anyone may hold and move tokens
+    client.mint(&outsider, &100i128);
+    client.transfer(&outsider, &user1, &50i128);
+
+    client.add_to_allowlist(&user1);
+    client.add_to_allowlist(&user2);
+    assert!(client.is_allowed(&user1));
+    assert!(!client.is_allowed(&outsider));
+    client.set_allowlist_enabled(&true);
+
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user2, &100i128);
+    client.approve(&user1, &spender, &100i128, &1000u32);
+    client.transfer_from(&spender, &user1, &user2, &100i128);
+    assert_eq!(client.balance(&user2), 200i128);
+
+    assert_eq!(
+        client.try_mint(&outsider, &100i128),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+    assert_eq!(
+        client.try_transfer(&user1, &outsider, &10i128),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+    assert_eq!(
+        client.try_transfer(&outsider, &user1, &10i128),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+
+    client.remove_from_allowlist(&user2);
+    assert!(!client.is_allowed(&user2));
+    client.approve(&user1, &spender, &10i128, &1000u32);
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &10i128),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+
+    client.set_allowlist_enabled(&false);
+    client.transfer(&user1, &outsider, &10i128);
+    assert_eq!(client.balance(&outsider), 60i128);
+}
+
+#[test]
+fn test_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(
+        client.metadata(),
+        (client.name(), client.symbol(), client.decimals())
+    );
+    assert_eq!(
+        client.metadata(),
+        (
+            String::from_str(&env, "Test Token"),
+            String::from_str(&env, "TEST"),
+            7u32,
+        )
+    );
+}
+
+#[test]
+fn test_set_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let name = String::from_str(&env, "Renamed Token");
+    let symbol = String::from_str(&env, "RNM");
+
+    client.set_metadata(&Some(name.clone()), &None);
+    assert_eq!(client.metadata(), (name.clone(), String::from_str(&env, "TEST"), 7u32));
+
+    client.set_metadata(&None, &Some(String::from_str(&env, "NEW")));
+    assert_eq!(client.metadata(), (name.clone(), String::from_str(&env, "NEW"), 7u32));
+
+    let mark = event_mark(&env);
+    client.set_metadata(&Some(String::from_str(&env, "Final Token")), &Some(symbol.clone()));
+    assert_eq!(client.metadata(), (String::from_str(&env, "Final Token"), symbol.clone(), 7u32));
+    assert_events_since(&env, mark, &["set_metadata"]);
+    let (_, _, data) = env.events().all().last().unwrap();
+    let data: (String, String) = data.into_val(&env);
+    assert_eq!(data, (String::from_str(&env, "Final Token"), symbol));
+}
+
+#[test]
+fn test_set_metadata_validates_lengths() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(
+        client.try_set_metadata(&Some(String::from_str(&env, "")), &None),
+        Err(Ok(TokenError::InvalidMetadata.into()))
+    );
+    assert_eq!(
+        client.try_set_metadata(&None, &Some(String::from_str(&env, "THIRTEENCHARS"))),
+        Err(Ok(TokenError::InvalidMetadata.into()))
+    );
+    assert_eq!(client.symbol(), String::from_str(&env, "TEST"));
+}
+
+#[test]
+fn test_metadata_readers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = register_token(&env);
+
+    // Before initialize the readers fall back to empty values
+    env.as_contract(&client.address, || {
+        assert_eq!(read_name(&env), String::from_str(&env, ""));
+        assert_eq!(read_symbol(&env), String::from_str(&env, ""));
+        assert_eq!(read_decimal(&env), 0);
+    });
+
+    client.initialize(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "Test Token"),
+        &String::from_str(&env, "TEST"),
+        &None,
+        &true,
+        &true,
+        &true,
+        &None,
+    );
+
+    // Metadata no longer shares a key with the token state
+    client.mint(&admin, &100i128);
+    env.as_contract(&client.address, || {
+        assert_eq!(read_name(&env), String::from_str(&env, "Test Token"));
+        assert_eq!(read_symbol(&env), String::from_str(&env, "TEST"));
+        assert_eq!(read_decimal(&env), 7);
+    });
+}
+
+#[test]
+fn test_two_of_three_admin_mint() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let admin1 = Address::generate(&env);
+    let admin2 = Address::generate(&env);
+    let admin3 = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_admins(&vec![&env, admin1.clone(), admin2.clone(), admin3.clone()], &2u32);
+    assert_eq!(client.admin_set().unwrap().threshold, 2u32);
+
+    // The single admin alone can no longer act
+    assert_eq!(
+        client.try_mint(&user, &100i128),
+        Err(Ok(TokenError::ThresholdRequired.into()))
+    );
+
+    let hash = client.propose_action(&admin1, &AdminAction::Mint(user.clone(), 100i128));
+    assert_eq!(client.balance(&user), 0i128);
+    assert_eq!(client.proposal(&hash).unwrap().approvals.len(), 1);
+
+    assert_eq!(
+        client.try_approve_action(&admin1, &hash),
+        Err(Ok(TokenError::AlreadyApproved.into()))
+    );
+    assert_eq!(
+        client.try_approve_action(&outsider, &hash),
+        Err(Ok(TokenError::NotAnAdmin.into()))
+    );
+
+    client.approve_action(&admin3, &hash);
+    assert_eq!(client.balance(&user), 100i128);
+    assert!(client.proposal(&hash).is_none());
+    assert_eq!(
+        client.try_mint(&user, &100i128),
+        Err(Ok(TokenError::ThresholdRequired.into()))
+    );
+
+    // Dissolving the set hands control back to the single admin
+    let hash = client.propose_action(&admin2, &AdminAction::SetAdmins(vec![&env], 0u32));
+    client.approve_action(&admin1, &hash);
+    assert!(client.admin_set().is_none());
+    client.mint(&user, &50i128);
+    assert_eq!(client.balance(&user), 150i128);
+}
+
+#[test]
+fn test_removed_admin_approvals_do_not_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let admin1 = Address::generate(&env);
+    let admin2 = Address::generate(&env);
+    let admin3 = Address::generate(&env);
+    let admin4 = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_admins(&vec![&env, admin1.clone(), admin2.clone(), admin3.clone()], &2u32);
+    let mint = client.propose_action(&admin1, &AdminAction::Mint(user.clone(), 100i128));
+
+    // admin1 leaves the set before the mint reaches its threshold
+    let hash = client.propose_action(
+        &admin2,
+        &AdminAction::SetAdmins(vec![&env, admin2.clone(), admin3.clone(), admin4.clone()], 2u32),
+    );
+    client.approve_action(&admin3, &hash);
+
+    client.approve_action(&admin4, &mint);
+    assert_eq!(client.balance(&user), 0i128);
+    assert_eq!(client.proposal(&mint).unwrap().approvals, vec![&env, admin4.clone()]);
+
+    client.approve_action(&admin2, &mint);
+    assert_eq!(client.balance(&user), 100i128);
+}
+
+#[test]
+fn test_admin_set_blocks_calls_without_an_action() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let admin1 = Address::generate(&env);
+    let admin2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_admins(&vec![&env, admin1.clone(), admin2.clone()], &2u32);
+    assert_eq!(
+        client.try_set_max_wallet_balance(&Some(500i128)),
+        Err(Ok(TokenError::ThresholdRequired.into()))
+    );
+
+    // Such calls come back once the set hands control to the single admin
+    let hash = client.propose_action(&admin1, &AdminAction::SetAdmins(vec![&env], 0u32));
+    client.approve_action(&admin2, &hash);
+    client.set_max_wallet_balance(&Some(500i128));
+}
+
+#[test]
+fn test_event_topics_carry_addresses() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user2, &300i128);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("transfer", user1.clone(), user2.clone()).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 300i128);
+
+    client.burn_self(&user2, &100i128);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("burn", user2.clone()).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 100i128);
+
+    // The fee leg is indexed under the collector like any other transfer
+    client.set_transfer_fee(&1000u32, &Some(treasury.clone()));
+    client.transfer(&user1, &user2, &100i128);
+    let events = env.events().all();
+    let (_, topics, data) = events.get(events.len() - 2).unwrap();
+    assert_eq!(topics, ("transfer", user1.clone(), treasury).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 10i128);
+}
+
+#[test]
+fn test_admin_in_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let reason = String::from_str(&env, "compliance");
+
+    client.mint(&user, &100i128);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("mint", admin.clone(), user.clone()).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 100i128);
+
+    client.freeze(&user, &reason);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("freeze",).into_val(&env));
+    let data: (Address, Address, String) = data.into_val(&env);
+    assert_eq!(data, (admin.clone(), user.clone(), reason));
+
+    // Under an admin set the approver that executed the action is recorded
+    let admin1 = Address::generate(&env);
+    let admin2 = Address::generate(&env);
+    client.set_admins(&vec![&env, admin1.clone(), admin2.clone()], &2u32);
+    let hash = client.propose_action(&admin1, &AdminAction::Mint(user.clone(), 50i128));
+    client.approve_action(&admin2, &hash);
+
+    let mint_topics = ("mint", admin2, user).into_val(&env);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| *topics == mint_topics)
+        .last()
+        .unwrap();
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 50i128);
+}
+
+#[test]
+fn test_admin_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_admin_timelock(&100u32);
+    assert_eq!(
+        client.try_set_admin(&new_admin),
+        Err(Ok(TokenError::InvalidConfig.into()))
+    );
+
+    client.propose_admin(&new_admin);
+    assert_eq!(client.pending_admin().unwrap().ready_at, env.ledger().sequence() + 100);
+
+    advance_ledger(&env, 99);
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(GuardError::AdminTimelockActive.into()))
+    );
+    assert_eq!(client.admin(), admin);
+
+    advance_ledger(&env, 1);
+    client.accept_admin();
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(client.admin(), new_admin);
+    assert!(client.pending_admin().is_none());
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(TokenError::ProposalNotFound.into()))
+    );
+}
+
+#[test]
+fn test_admin_timelock_lowering_waits_out_current() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.set_admin_timelock(&10_000u32);
+    client.set_admin_timelock(&0u32);
+    assert_eq!(
+        client.try_set_admin(&new_admin),
+        Err(Ok(TokenError::InvalidConfig.into()))
+    );
+    client.propose_admin(&new_admin);
+    assert_eq!(client.pending_admin().unwrap().ready_at, env.ledger().sequence() + 10_000);
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(GuardError::AdminTimelockActive.into()))
+    );
+
+    // Once the old timelock has run, the lower one is in force
+    advance_ledger(&env, 10_000);
+    client.set_admin(&new_admin);
+    assert_eq!(client.admin(), new_admin);
+}
+
+#[test]
+fn test_set_admins_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let admin1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert!(client.try_set_admins(&vec![&env, admin1.clone()], &2u32).is_err());
+    assert!(client.try_set_admins(&vec![&env, admin1.clone()], &0u32).is_err());
+    assert!(client
+        .try_set_admins(&vec![&env, admin1.clone(), admin1.clone()], &1u32)
+        .is_err());
+    assert!(client.admin_set().is_none());
+}
+
+#[test]
+fn test_deposit_and_withdraw() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let asset_admin = token::StellarAssetClient::new(&env, &asset.address());
+    let asset_token = token::Client::new(&env, &asset.address());
+    asset_admin.mint(&user, &1000i128);
+
+    assert_eq!(
+        client.try_deposit(&user, &100i128),
+        Err(Ok(TokenError::InvalidConfig.into()))
+    );
+    client.set_underlying_asset(&asset.address());
+    assert_eq!(
+        client.try_set_underlying_asset(&Address::generate(&env)),
+        Err(Ok(TokenError::InvalidConfig.into()))
+    );
+
+    // A deposit is a mint by the wrapper contract itself
+    client.deposit(&user, &600i128);
+    let (contract, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(contract, client.address);
+    assert_eq!(topics, ("mint", client.address.clone(), user.clone()).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 600i128);
+    assert_eq!(client.balance(&user), 600i128);
+    assert_eq!(asset_token.balance(&user), 400i128);
+    assert_eq!(asset_token.balance(&client.address), client.total_supply());
+
+    client.withdraw(&user, &250i128);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, ("burn", user.clone()).into_val(&env));
+    let data: i128 = data.into_val(&env);
+    assert_eq!(data, 250i128);
+    assert_eq!(client.balance(&user), 350i128);
+    assert_eq!(asset_token.balance(&user), 650i128);
+    assert_eq!(asset_token.balance(&client.address), client.total_supply());
+
+    // The backing cannot be withdrawn past the wrapped balance or rescued
+    assert!(client.try_withdraw(&user, &351i128).is_err());
+    assert_eq!(
+        client.try_rescue_asset(&asset.address(), &admin, &350i128),
+        Err(Ok(TokenError::CannotRescueOwnToken.into()))
+    );
+}
+
+#[test]
+fn test_underlying_asset_keeps_supply_backed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+
+    // Existing supply would be unbacked
+    client.mint(&user, &100i128);
+    assert_eq!(
+        client.try_set_underlying_asset(&asset.address()),
+        Err(Ok(GuardError::UnbackedSupply.into()))
+    );
+    client.admin_burn(&user, &100i128);
+    client.set_underlying_asset(&asset.address());
+
+    // Only deposits mint from here on
+    assert_eq!(
+        client.try_mint(&user, &100i128),
+        Err(Ok(GuardError::UnbackedSupply.into()))
+    );
+    assert_eq!(
+        client.try_mint_up_to(&user, &100i128),
+        Err(Ok(GuardError::UnbackedSupply.into()))
+    );
+    assert_eq!(client.total_supply(), 0i128);
+}
+
+#[test]
+fn test_deposit_applies_mint_checks() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&user, &1000i128);
+    client.set_underlying_asset(&asset.address());
+
+    client.set_max_wallet_balance(&Some(500i128));
+    assert_eq!(
+        client.try_deposit(&user, &600i128),
+        Err(Ok(TokenError::ExceedsWalletLimit.into()))
+    );
+    client.set_max_wallet_balance(&None);
+
+    client.set_allowlist_enabled(&true);
+    assert_eq!(
+        client.try_deposit(&user, &600i128),
+        Err(Ok(TokenError::NotAllowlisted.into()))
+    );
+    client.add_to_allowlist(&user);
+    client.deposit(&user, &600i128);
+    assert_eq!(client.balance(&user), 600i128);
+    assert_eq!(token::Client::new(&env, &asset.address()).balance(&user), 400i128);
+}
+
+#[test]
+fn test_rescue_asset() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    let foreign = env.register_stellar_asset_contract_v2(admin.clone());
+    let foreign_admin = token::StellarAssetClient::new(&env, &foreign.address());
+    let foreign_token = token::Client::new(&env, &foreign.address());
+
+    // Someone sends a foreign asset to the token contract by mistake
+    foreign_admin.mint(&client.address, &500i128);
+
+    client.rescue_asset(&foreign.address(), &user, &500i128);
+    assert_eq!(foreign_token.balance(&user), 500i128);
+    assert_eq!(foreign_token.balance(&client.address), 0i128);
+
+    client.mint(&client.address, &100i128);
+    assert_eq!(
+        client.try_rescue_asset(&client.address, &user, &100i128),
+        Err(Ok(TokenError::CannotRescueOwnToken.into()))
+    );
+}
+
+#[test]
+fn test_set_max_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &500i128);
+    client.set_max_supply(&Some(500i128));
+    assert_eq!(
+        client.try_mint(&user1, &1i128),
+        Err(Ok(TokenError::ExceedsMaxSupply.into()))
+    );
+    assert_eq!(
+        client.try_set_max_supply(&Some(499i128)),
+        Err(Ok(TokenError::ExceedsMaxSupply.into()))
+    );
+
+    client.set_max_supply(&None);
+    client.mint(&user1, &1i128);
+}
+
+#[test]
+fn test_renounce_minting() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &500i128);
+    client.renounce_minting();
+    assert!(!client.is_mintable());
+
+    assert_eq!(
+        client.try_mint(&user1, &1i128),
+        Err(Ok(TokenError::NotMintable.into()))
+    );
+    assert_eq!(
+        client.try_set_max_supply(&Some(1000i128)),
+        Err(Ok(TokenError::NotMintable.into()))
+    );
+    assert_eq!(
+        client.try_renounce_minting(),
+        Err(Ok(TokenError::NotMintable.into()))
+    );
+
+    // Existing balances are untouched
+    client.transfer(&user1, &admin, &100i128);
+    assert_eq!(client.total_supply(), 500i128);
+}
+
+#[test]
+fn test_disable_freezing_unsticks_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.freeze(&user1, &String::from_str(&env, "compliance"));
+    client.set_frozen(&true);
+    client.disable_freezing();
+    assert!(!client.is_freezable());
+
+    // Both the global and the account freeze can still be lifted
+    client.set_frozen(&false);
+    client.unfreeze(&user1);
+    client.transfer(&user1, &user2, &100i128);
+    assert_eq!(client.balance(&user2), 100i128);
+
+    assert_eq!(
+        client.try_set_frozen(&true),
+        Err(Ok(TokenError::NotFreezable.into()))
+    );
+    assert_eq!(
+        client.try_freeze(&user1, &String::from_str(&env, "compliance")),
+        Err(Ok(TokenError::NotFreezable.into()))
+    );
+}
+
+#[test]
+fn test_admin_set_unsticks_global_freeze() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let admin1 = Address::generate(&env);
+    let admin2 = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.set_frozen(&true);
+    client.disable_freezing();
+    client.set_admins(&vec![&env, admin1.clone(), admin2.clone()], &2u32);
+
+    // Handing control to an admin set must not lock the freeze in place either
+    let hash = client.propose_action(&admin1, &AdminAction::SetFrozen(false));
+    client.approve_action(&admin2, &hash);
+    assert!(!client.is_emergency());
+    client.transfer(&user1, &user2, &100i128);
+    assert_eq!(client.balance(&user2), 100i128);
+}
+
+#[test]
+fn test_transfer_from_reports_remaining_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &500i128, &1000u32);
+    let mark = event_mark(&env);
+    client.transfer_from(&spender, &user1, &user2, &200i128);
+    assert_events_since(&env, mark, &["approve", "transfer"]);
+
+    let events = env.events().all();
+    let (_, _, data) = events.get(events.len() - 2).unwrap();
+    let data: (Address, Address, i128, u32) = data.into_val(&env);
+    assert_eq!(data, (user1.clone(), spender.clone(), 300i128, 1000u32));
+}
+
+#[test]
+fn test_mint_returns_new_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    assert_eq!(client.mint(&user1, &1000i128), 1000i128);
+    assert_eq!(client.mint(&user2, &250i128), 1250i128);
+    assert_eq!(client.total_supply(), 1250i128);
+}
+
+#[test]
+fn test_zero_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.approve(&user1, &spender, &100i128, &1000u32);
+
+    // By default a zero transfer succeeds without emitting anything
+    let events_before = env.events().all().len();
+    client.transfer(&user1, &user2, &0i128);
+    client.transfer_from(&spender, &user1, &user2, &0i128);
+    assert_eq!(env.events().all().len(), events_before);
+    assert_eq!(client.balance(&user1), 1000i128);
+    assert_eq!(client.allowance(&user1, &spender), 100i128);
+
+    client.set_reject_zero_transfers(&true);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &0i128),
+        Err(Ok(TokenError::ZeroAmount.into()))
+    );
+    assert_eq!(
+        client.try_transfer_from(&spender, &user1, &user2, &0i128),
+        Err(Ok(TokenError::ZeroAmount.into()))
+    );
+    client.transfer(&user1, &user2, &1i128);
+    assert_eq!(client.balance(&user2), 1i128);
+}
+
+/// Skew the stored supply by `delta` without going through `write_state`.
+#[cfg(feature = "supply-invariant")]
+fn corrupt_total_supply(env: &Env, client: &TokenContractClient, delta: i128) {
+    env.as_contract(&client.address, || {
+        let mut state = crate::admin::read_state(env);
+        state.total_supply += delta;
+        env.storage().instance().set(&DataKey::State, &state);
+    });
+}
+
+#[test]
+#[cfg(feature = "supply-invariant")]
+fn test_supply_invariant_holds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    client.transfer(&user1, &user2, &400i128);
+    client.burn_self(&user2, &100i128);
+    client.admin_burn(&user1, &600i128);
+    assert_eq!(client.total_supply(), 300i128);
+}
+
+#[test]
+#[cfg(feature = "supply-invariant")]
+fn test_supply_invariant_catches_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    corrupt_total_supply(&env, &client, 1);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &100i128),
+        Err(Ok(GuardError::SupplyInvariantViolated.into()))
+    );
+}
+
+#[test]
+#[cfg(feature = "supply-invariant")]
+fn test_supply_invariant_catches_mint_and_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    client.mint(&user1, &1000i128);
+    corrupt_total_supply(&env, &client, -1);
+    assert_eq!(
+        client.try_mint(&user1, &100i128),
+        Err(Ok(GuardError::SupplyInvariantViolated.into()))
+    );
+    assert_eq!(
+        client.try_burn_self(&user1, &100i128),
+        Err(Ok(GuardError::SupplyInvariantViolated.into()))
+    );
+}
+
+#[test]
+#[cfg(feature = "supply-invariant")]
+fn test_supply_invariant_catches_negative_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let client = create_token(&env, &admin);
+
+    corrupt_total_supply(&env, &client, -1);
+    assert_eq!(
+        client.try_mint(&user1, &1i128),
+        Err(Ok(GuardError::SupplyInvariantViolated.into()))
+    );
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Events, Env, IntoVal, Val, Vec};
+
+/// Number of events emitted so far, to pass to `assert_events_since`.
+pub fn event_mark(env: &Env) -> u32 {
+    env.events().all().len()
+}
+
+/// Assert the events emitted after `mark` match `expected` by first topic, in count and order.
+pub fn assert_events_since(env: &Env, mark: u32, expected: &[&str]) {
+    let events = env.events().all().slice(mark..);
+    assert_eq!(events.len() as usize, expected.len(), "event count");
+    for (i, (_, topics, _)) in events.iter().enumerate() {
+        let name: Vec<Val> = (expected[i],).into_val(env);
+        assert_eq!(topics.slice(0..1), name, "event {} should be {}", i, expected[i]);
+    }
+}
\ No newline at end of file
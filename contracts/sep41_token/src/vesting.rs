@@ -0,0 +1,76 @@
+use crate::storage_types::{
+    DataKey, TrancheSchedule, VestingSchedule, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env};
+
+pub fn next_tranche_id(e: &Env) -> u64 {
+    let key = DataKey::TrancheCount;
+    let id: u64 = e.storage().instance().get(&key).unwrap_or(0);
+    e.storage().instance().set(&key, &(id + 1));
+    id
+}
+
+pub fn read_tranche(e: &Env, id: u64) -> Option<TrancheSchedule> {
+    let key = DataKey::Tranche(id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_tranche(e: &Env, id: u64, schedule: &TrancheSchedule) {
+    let key = DataKey::Tranche(id);
+    e.storage().persistent().set(&key, schedule);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_tranche(e: &Env, id: u64) {
+    let key = DataKey::Tranche(id);
+    e.storage().persistent().remove(&key);
+}
+
+/// Amount released by `schedule` so far; the final tranche carries any rounding dust.
+pub fn tranche_unlocked(e: &Env, schedule: &TrancheSchedule) -> i128 {
+    let elapsed = e.ledger().sequence().saturating_sub(schedule.start_ledger);
+    let released = elapsed
+        .checked_div(schedule.interval_ledgers)
+        .unwrap_or(schedule.tranches);
+
+    if released >= schedule.tranches {
+        schedule.total
+    } else {
+        (schedule.total / schedule.tranches as i128) * released as i128
+    }
+}
+
+pub fn read_vesting(e: &Env, beneficiary: Address) -> Option<VestingSchedule> {
+    let key = DataKey::Vesting(beneficiary);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_vesting(e: &Env, beneficiary: Address, schedule: &VestingSchedule) {
+    let key = DataKey::Vesting(beneficiary);
+    e.storage().persistent().set(&key, schedule);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_vesting(e: &Env, beneficiary: Address) {
+    let key = DataKey::Vesting(beneficiary);
+    e.storage().persistent().remove(&key);
+}
+
+/// Amount released by a linear `schedule` so far: nothing before the start,
+/// everything once the duration has elapsed.
+pub fn vested_amount(e: &Env, schedule: &VestingSchedule) -> i128 {
+    let now = e.ledger().sequence();
+    if now <= schedule.start_ledger {
+        return 0;
+    }
+    let elapsed = now - schedule.start_ledger;
+    if elapsed >= schedule.duration_ledgers {
+        schedule.total
+    } else {
+        schedule.total * elapsed as i128 / schedule.duration_ledgers as i128
+    }
+}
\ No newline at end of file
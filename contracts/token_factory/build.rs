@@ -0,0 +1,4 @@
+// `contractimport!` embeds the token WASM, which cargo does not track on its own
+fn main() {
+    println!("cargo:rerun-if-changed=../sep41_token.wasm");
+}
\ No newline at end of file
@@ -0,0 +1,120 @@
+use crate::error::FactoryError;
+use crate::storage_types::{
+    DataKey, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{contract, contractimpl, panic_with_error, vec, xdr::ToXdr, Address, BytesN, Env, String, Vec};
+
+pub mod token {
+    // Built by contracts/build.sh
+    soroban_sdk::contractimport!(file = "../sep41_token.wasm");
+}
+
+#[contract]
+pub struct TokenFactory;
+
+#[contractimpl]
+impl TokenFactory {
+    /// Set the factory admin and the uploaded token WASM that `create_token`
+    /// deploys (once); deploy and initialize in one transaction
+    pub fn init_factory(env: Env, admin: Address, wasm_hash: BytesN<32>) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, FactoryError::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::TokenWasm, &wasm_hash);
+        extend_instance(&env);
+    }
+
+    /// Replace the token WASM deployed by later `create_token` calls (admin only)
+    pub fn set_token_wasm(env: Env, wasm_hash: BytesN<32>) {
+        read_admin(&env).require_auth();
+        env.storage().instance().set(&DataKey::TokenWasm, &wasm_hash);
+        extend_instance(&env);
+    }
+
+    /// Get the factory admin
+    pub fn admin(env: Env) -> Address {
+        read_admin(&env)
+    }
+
+    /// Deploy and initialize a mintable, burnable, freezable token at an address
+    /// derived from this factory, `admin` and `salt`, returning its address (admin's auth)
+    pub fn create_token(
+        env: Env,
+        admin: Address,
+        decimal: u32,
+        name: String,
+        symbol: String,
+        salt: BytesN<32>,
+    ) -> Address {
+        admin.require_auth();
+
+        let wasm_hash: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenWasm)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+
+        // Mixing in the admin keeps another caller from front-running the address
+        let mut preimage = admin.clone().to_xdr(&env);
+        preimage.append(&salt.into());
+        let salt: BytesN<32> = env.crypto().sha256(&preimage).into();
+
+        let token = env.deployer().with_current_contract(salt).deploy(wasm_hash);
+        token::Client::new(&env, &token).initialize(
+            &admin, &decimal, &name, &symbol, &None, &true, &true, &true, &None,
+        );
+
+        append_token(&env, &token);
+        extend_instance(&env);
+
+        env.events().publish(
+            ("create_token",), 
+            (token.clone(), admin)
+        );
+        token
+    }
+
+    /// Get up to `limit` of the tokens created by this factory, oldest first,
+    /// starting at index `start`
+    pub fn tokens(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        let count = read_token_count(&env);
+        let end = start.saturating_add(limit).min(count);
+        let mut tokens = vec![&env];
+        for index in start.min(end)..end {
+            let token: Address = env.storage().persistent().get(&DataKey::Token(index)).unwrap();
+            tokens.push_back(token);
+        }
+        tokens
+    }
+}
+
+fn read_admin(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .unwrap_or_else(|| panic_with_error!(e, FactoryError::NotInitialized))
+}
+
+fn read_token_count(e: &Env) -> u32 {
+    e.storage().instance().get(&DataKey::TokenCount).unwrap_or(0)
+}
+
+// One persistent entry per token keeps the registry out of instance storage,
+// which every call loads
+fn append_token(e: &Env, token: &Address) {
+    let count = read_token_count(e);
+    let key = DataKey::Token(count);
+    e.storage().persistent().set(&key, token);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    e.storage().instance().set(&DataKey::TokenCount, &(count + 1));
+}
+
+fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
\ No newline at end of file
@@ -0,0 +1,10 @@
+#![no_std]
+
+pub mod contract;
+pub mod error;
+mod storage_types;
+
+#[cfg(test)]
+mod test;
+
+pub use contract::{TokenFactory, TokenFactoryClient};
\ No newline at end of file
@@ -0,0 +1,125 @@
+#![cfg(test)]
+
+use crate::contract::{token, TokenFactory, TokenFactoryClient};
+use crate::error::FactoryError;
+use soroban_sdk::{
+    testutils::{Address as _, MockAuth, MockAuthInvoke},
+    vec, Address, BytesN, Env, IntoVal, String,
+};
+
+fn create_factory<'a>(env: &Env, admin: &Address) -> TokenFactoryClient<'a> {
+    let factory_id = env.register_contract(None, TokenFactory);
+    let factory = TokenFactoryClient::new(env, &factory_id);
+    let wasm_hash = env.deployer().upload_contract_wasm(token::WASM);
+    factory.init_factory(admin, &wasm_hash);
+    factory
+}
+
+#[test]
+fn test_create_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let factory = create_factory(&env, &admin);
+    assert_eq!(factory.admin(), admin);
+
+    let first = factory.create_token(
+        &admin,
+        &7u32,
+        &String::from_str(&env, "First Token"),
+        &String::from_str(&env, "ONE"),
+        &BytesN::from_array(&env, &[1; 32]),
+    );
+    let second = factory.create_token(
+        &admin,
+        &2u32,
+        &String::from_str(&env, "Second Token"),
+        &String::from_str(&env, "TWO"),
+        &BytesN::from_array(&env, &[2; 32]),
+    );
+
+    assert_ne!(first, second);
+    assert_eq!(factory.tokens(&0u32, &10u32), vec![&env, first.clone(), second.clone()]);
+    assert_eq!(factory.tokens(&1u32, &1u32), vec![&env, second.clone()]);
+
+    // Each token is initialized and administered by `admin`
+    let token = token::Client::new(&env, &second);
+    assert_eq!(token.symbol(), String::from_str(&env, "TWO"));
+    assert_eq!(token.decimals(), 2u32);
+    let user = Address::generate(&env);
+    token.mint(&user, &100i128);
+    assert_eq!(token.balance(&user), 100i128);
+    assert_eq!(token::Client::new(&env, &first).total_supply(), 0i128);
+}
+
+#[test]
+fn test_factory_rejects_reinitialization() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let factory = create_factory(&env, &admin);
+
+    assert!(factory.tokens(&0u32, &10u32).is_empty());
+    assert_eq!(
+        factory.try_init_factory(&Address::generate(&env), &BytesN::from_array(&env, &[0; 32])),
+        Err(Ok(FactoryError::AlreadyInitialized.into()))
+    );
+    assert_eq!(factory.admin(), admin);
+}
+
+#[test]
+fn test_init_factory_requires_admin_auth() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let factory_id = env.register_contract(None, TokenFactory);
+    let factory = TokenFactoryClient::new(&env, &factory_id);
+    let wasm_hash = env.deployer().upload_contract_wasm(token::WASM);
+
+    assert!(factory.try_init_factory(&admin, &wasm_hash).is_err());
+
+    factory
+        .mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &factory_id,
+                fn_name: "init_factory",
+                args: (admin.clone(), wasm_hash.clone()).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .init_factory(&admin, &wasm_hash);
+    assert_eq!(factory.admin(), admin);
+
+    // Only the admin may swap the deployed WASM
+    let other = BytesN::from_array(&env, &[7; 32]);
+    assert!(factory.try_set_token_wasm(&other).is_err());
+}
+
+#[test]
+fn test_create_token_salt_is_per_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let other = Address::generate(&env);
+    let factory = create_factory(&env, &admin);
+    let salt = BytesN::from_array(&env, &[1; 32]);
+    let name = String::from_str(&env, "Token");
+    let symbol = String::from_str(&env, "TKN");
+
+    // Creating a token for `admin` needs admin's own auth
+    env.set_auths(&[]);
+    assert!(factory.try_create_token(&admin, &7u32, &name, &symbol, &salt).is_err());
+
+    env.mock_all_auths();
+    let first = factory.create_token(&admin, &7u32, &name, &symbol, &salt);
+    let second = factory.create_token(&other, &7u32, &name, &symbol, &salt);
+    assert_ne!(first, second);
+    assert_eq!(factory.tokens(&0u32, &10u32), vec![&env, first, second]);
+    assert!(factory.try_create_token(&admin, &7u32, &name, &symbol, &salt).is_err());
+}
\ No newline at end of file